@@ -0,0 +1,242 @@
+//! Named actions
+//! Every command of the main window is a `gio` action on the application
+//! (`app.*`) or the main window (`win.*`). Buttons, keyboard shortcuts, the
+//! shortcuts window and `--action` on the command line all go through them.
+
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::app_state::AppState;
+use crate::default_profile::is_default_profile;
+
+pub struct ActionInfo {
+    /// Full name, e.g. "win.save"
+    pub name: &'static str,
+    pub title: &'static str,
+    /// Heading in the shortcuts window
+    pub section: &'static str,
+    pub accels: &'static [&'static str],
+    /// The accelerators only work while the sidebar has focus, so they do not
+    /// steal keys text entries need
+    pub sidebar_only: bool,
+}
+
+/// Every action, in the order of the shortcuts window and `--list-actions`
+pub const ACTIONS: &[ActionInfo] = &[
+    ActionInfo { name: "app.new-profile", title: "New profile", section: "Profiles", accels: &["<Control>n"], sidebar_only: false },
+    ActionInfo { name: "win.add-default-profile", title: "Add default profile", section: "Profiles", accels: &[], sidebar_only: false },
+    ActionInfo { name: "win.save", title: "Save changes", section: "Profiles", accels: &["<Control>s"], sidebar_only: false },
+    ActionInfo { name: "win.save-as-template", title: "Save as template", section: "Profiles", accels: &["<Control><Shift>s"], sidebar_only: false },
+    ActionInfo { name: "win.rename-profile", title: "Rename profile", section: "Profiles", accels: &["F2"], sidebar_only: false },
+    ActionInfo { name: "win.duplicate-profile", title: "Duplicate profile", section: "Profiles", accels: &["<Control>d"], sidebar_only: false },
+    ActionInfo { name: "win.delete-profile", title: "Remove selected profiles", section: "Profiles", accels: &["Delete"], sidebar_only: true },
+    ActionInfo { name: "win.launch-profile", title: "Launch with profile", section: "Profiles", accels: &["<Control>Return"], sidebar_only: false },
+    ActionInfo { name: "win.search", title: "Search profiles", section: "Sidebar", accels: &["<Control>f"], sidebar_only: false },
+    ActionInfo { name: "win.bulk-edit", title: "Edit selected profiles", section: "Sidebar", accels: &["<Control>e"], sidebar_only: false },
+    ActionInfo { name: "win.compare-profiles", title: "Compare selected profiles", section: "Sidebar", accels: &[], sidebar_only: false },
+    ActionInfo { name: "win.export-profiles", title: "Export selected profiles", section: "Sidebar", accels: &[], sidebar_only: false },
+    ActionInfo { name: "app.preferences", title: "Settings", section: "General", accels: &["<Control>comma"], sidebar_only: false },
+    ActionInfo { name: "app.history", title: "Config history", section: "General", accels: &["<Control>h"], sidebar_only: false },
+    ActionInfo { name: "app.shortcuts", title: "Keyboard shortcuts", section: "General", accels: &["<Control>question"], sidebar_only: false },
+    ActionInfo { name: "app.quit", title: "Quit", section: "General", accels: &["<Control>q"], sidebar_only: false },
+];
+
+/// Application action that runs another action by name; how `--action`
+/// reaches an instance that is already running
+pub const RUN_ACTION: &str = "run-action";
+
+pub fn find_action(name: &str) -> Option<&'static ActionInfo> {
+    ACTIONS.iter().find(|action| action.name == name)
+}
+
+/// Registers every action on the application and main window, with its accelerators
+pub fn setup_actions(app: &libadwaita::Application, app_state: &Rc<RefCell<AppState>>) {
+    let main_window = app_state.borrow().main_window.clone();
+    for info in ACTIONS {
+        let (scope, short_name) = info.name.split_once('.').unwrap_or(("win", info.name));
+        let action = gio::SimpleAction::new(short_name, None);
+        let app_state_clone = app_state.clone();
+        action.connect_activate(move |_, _| activate(&app_state_clone, info.name));
+        match scope {
+            "app" => app.add_action(&action),
+            _ => main_window.add_action(&action),
+        }
+        if info.sidebar_only {
+            add_sidebar_shortcuts(&app_state.borrow().sidebar_list_box, info);
+        } else {
+            app.set_accels_for_action(info.name, info.accels);
+        }
+    }
+
+    let run_action = gio::SimpleAction::new(RUN_ACTION, Some(glib::VariantTy::STRING));
+    let app_state_clone = app_state.clone();
+    run_action.connect_activate(move |_, parameter| {
+        let Some(name) = parameter.and_then(|parameter| parameter.get::<String>()) else {
+            return;
+        };
+        match find_action(&name) {
+            Some(info) => activate(&app_state_clone, info.name),
+            None => eprintln!("Unknown action: {}", name),
+        }
+    });
+    app.add_action(&run_action);
+
+    refresh_action_states(&app_state.borrow());
+}
+
+fn add_sidebar_shortcuts(sidebar_list_box: &gtk::ListBox, info: &ActionInfo) {
+    let controller = gtk::ShortcutController::new();
+    for accel in info.accels {
+        match gtk::ShortcutTrigger::parse_string(accel) {
+            Some(trigger) => controller.add_shortcut(gtk::Shortcut::new(
+                Some(trigger),
+                Some(gtk::NamedAction::new(info.name)),
+            )),
+            None => eprintln!("Invalid accelerator for {}: {}", info.name, accel),
+        }
+    }
+    sidebar_list_box.add_controller(controller);
+}
+
+// Runs an action; dialogs borrow the state themselves, so it is released first
+fn activate(app_state: &Rc<RefCell<AppState>>, name: &str) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let selected = state.selected_profile_index;
+    let selection_count = state.selected_profile_indices().len();
+    let selected_is_default = selected
+        .and_then(|index| state.config.game.get(index))
+        .is_some_and(is_default_profile);
+    drop(state);
+
+    match name {
+        "app.new-profile" => crate::profile_manager::show_create_profile_dialog(app_state.clone()),
+        "app.preferences" => {
+            crate::settings_window::create_settings_window(&main_window, app_state.clone()).present();
+        }
+        "app.history" => crate::history_window::show_history_window(app_state.clone()),
+        "app.shortcuts" => show_shortcuts_window(&main_window),
+        // Closing the window saves the UI state; the application ends with its last window
+        "app.quit" => main_window.close(),
+        "win.add-default-profile" => {
+            let mut state = app_state.borrow_mut();
+            state.add_default_profile();
+            state.populate_sidebar_with_handlers(Some(app_state.clone()));
+            drop(state);
+            app_state.borrow().update_main_window_from_profile();
+        }
+        "win.save" => crate::signal_handlers::handle_save(app_state),
+        "win.save-as-template" => crate::profile_manager::show_save_template_dialog(app_state.clone()),
+        "win.rename-profile" => {
+            if let Some(index) = selected.filter(|_| !selected_is_default) {
+                crate::profile_manager::show_edit_profile_dialog(app_state.clone(), index);
+            }
+        }
+        "win.duplicate-profile" => {
+            if let Some(index) = selected {
+                crate::profile_manager::show_duplicate_profile_dialog(app_state.clone(), index);
+            }
+        }
+        "win.delete-profile" => {
+            if selection_count > 1 {
+                crate::bulk_edit::show_bulk_delete_dialog(app_state.clone());
+            } else if let Some(index) = selected.filter(|_| !selected_is_default) {
+                crate::profile_manager::show_remove_profile_dialog(app_state.clone(), index);
+            }
+        }
+        "win.launch-profile" => {
+            if let Some(index) = selected {
+                crate::launch_window::launch_profile(app_state.clone(), index);
+            }
+        }
+        "win.search" => {
+            let search_entry = app_state.borrow().sidebar_search_entry.clone();
+            search_entry.grab_focus();
+            search_entry.select_region(0, -1);
+        }
+        "win.bulk-edit" => crate::bulk_edit::show_bulk_edit_dialog(app_state.clone()),
+        "win.compare-profiles" => crate::compare::show_compare_window(app_state.clone()),
+        "win.export-profiles" => crate::bulk_edit::show_bulk_export_dialog(app_state.clone()),
+        _ => eprintln!("Unknown action: {}", name),
+    }
+}
+
+/// Enables the profile actions that apply to the current selection
+pub fn refresh_action_states(state: &AppState) {
+    let selection_count = state.selected_profile_indices().len();
+    let single = selection_count == 1 && state.selected_profile_index.is_some();
+    let selected_is_default = state.selected_profile_index
+        .and_then(|index| state.config.game.get(index))
+        .is_some_and(is_default_profile);
+
+    let states = [
+        ("save", single),
+        ("save-as-template", single),
+        ("rename-profile", single && !selected_is_default),
+        ("duplicate-profile", single),
+        ("delete-profile", selection_count > 1 || (single && !selected_is_default)),
+        ("launch-profile", single),
+        ("bulk-edit", selection_count > 0),
+        ("compare-profiles", selection_count > 1),
+        ("export-profiles", selection_count > 0),
+    ];
+    for (name, enabled) in states {
+        if let Some(action) = state.main_window.lookup_action(name).and_downcast::<gio::SimpleAction>() {
+            action.set_enabled(enabled);
+        }
+    }
+}
+
+// GtkShortcutsWindow can only be filled from a builder file, so one is
+// generated from `ACTIONS`
+fn shortcuts_ui() -> String {
+    let mut sections: Vec<&str> = Vec::new();
+    for action in ACTIONS {
+        if !sections.contains(&action.section) {
+            sections.push(action.section);
+        }
+    }
+
+    let mut groups = String::new();
+    for section in sections {
+        groups.push_str(&format!(
+            "<child><object class=\"GtkShortcutsGroup\"><property name=\"title\">{}</property>",
+            glib::markup_escape_text(section)
+        ));
+        for action in ACTIONS.iter().filter(|action| action.section == section && !action.accels.is_empty()) {
+            groups.push_str(&format!(
+                "<child><object class=\"GtkShortcutsShortcut\">\
+                 <property name=\"title\">{}</property>\
+                 <property name=\"accelerator\">{}</property>\
+                 </object></child>",
+                glib::markup_escape_text(action.title),
+                glib::markup_escape_text(&action.accels.join(" ")),
+            ));
+        }
+        groups.push_str("</object></child>");
+    }
+
+    format!(
+        "<interface><object class=\"GtkShortcutsWindow\" id=\"shortcuts_window\">\
+         <property name=\"modal\">true</property>\
+         <child><object class=\"GtkShortcutsSection\">\
+         <property name=\"section-name\">shortcuts</property>\
+         <property name=\"max-height\">12</property>\
+         {}</object></child></object></interface>",
+        groups
+    )
+}
+
+/// Shows the keyboard shortcuts of every action that has one
+pub fn show_shortcuts_window(parent: &libadwaita::ApplicationWindow) {
+    let builder = gtk::Builder::from_string(&shortcuts_ui());
+    match builder.object::<gtk::ShortcutsWindow>("shortcuts_window") {
+        Some(window) => {
+            window.set_transient_for(Some(parent));
+            window.present();
+        }
+        None => eprintln!("Failed to build the shortcuts window"),
+    }
+}
@@ -1,125 +1,832 @@
 use gtk::prelude::*;
 use gtk::{glib, ListBoxRow, Label, Button};
+use libadwaita::prelude::*;
 use libadwaita::ApplicationWindow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
-use crate::config::{Config, save_config};
-use crate::utils::round_to_2_decimals;
-use crate::signal_handlers::{with_blocked_signals, update_dropdown_selection};
-use crate::ui_components::LayoutFactory;
+use crate::config::{Config, GameProfile, save_config};
+use crate::profile_fields::{changed_fields, FieldValue, ProfileField};
+use crate::exe_matching::find_matching_profile;
+use crate::launch_window::launch_profile;
+use crate::desktop_launcher::sync_launchers;
+use crate::gamescope::gamescope_prefix;
+use crate::frame_math::{estimate_frame_rates, recommended_multiplier, suggest_present_mode, CALCULATOR_MULTIPLIERS};
+use crate::steam::{generate_launch_options, is_valid_app_id};
+use crate::profile_meta::{flatten_inheritance, record_profile_edit, resolve_profile, save_profile_meta, would_create_cycle, ProfileMetaFile};
+use crate::signal_handlers::with_blocked_signals;
+use crate::sidebar::{all_tags, sidebar_items, sidebar_order, FilterChip, SidebarFilter, SidebarItem, SortMode, SORT_MODES};
+use crate::utils::unix_time_now;
+use crate::ui_state::{UiState, MAX_SIDEBAR_WIDTH, MIN_SIDEBAR_WIDTH};
+use crate::profile_editor::FieldEditor;
+use crate::ui_components::{FeedbackUtils, LayoutFactory};
 use crate::styles::css_classes;
-use crate::profile_manager::{show_edit_profile_dialog, show_remove_profile_dialog};
+use crate::presets::{find_matching_preset, preset_catalog};
+use crate::default_profile::{is_default_profile, DEFAULT_PROFILE_EXE, DEFAULT_PROFILE_LABEL};
+use crate::profile_manager::{reorder_profile, show_duplicate_profile_dialog, show_edit_profile_dialog, show_remove_profile_dialog};
+
+/// A group header in the sidebar; kept across rebuilds so focus survives them
+pub struct SidebarGroupHeader {
+    pub row: ListBoxRow,
+    pub label: Label,
+    pub expander_icon: gtk::Image,
+}
 
 #[allow(dead_code)]
 pub struct AppState {
     pub config: Config,
+    pub profile_meta: ProfileMetaFile,
     pub selected_profile_index: Option<usize>,
     // Store references to the UI widgets for easy access and updates
     pub main_window: ApplicationWindow,
+    pub split_view: libadwaita::NavigationSplitView,
+    pub content_title: libadwaita::WindowTitle,
+    // Set once the sidebar has been dragged to a fixed width
+    pub sidebar_width: Cell<Option<i32>>,
     pub sidebar_list_box: gtk::ListBox,
-    pub multiplier_dropdown: gtk::DropDown,
-    pub flow_scale_entry: gtk::Entry,
-    pub performance_mode_switch: gtk::Switch,
-    pub hdr_mode_switch: gtk::Switch,
-    pub experimental_present_mode_dropdown: gtk::DropDown,
+    // One row per profile, in config order; the list box shows them filtered and sorted
+    pub sidebar_rows: RefCell<Vec<ListBoxRow>>,
+    pub sidebar_search_entry: gtk::SearchEntry,
+    pub sidebar_sort_dropdown: gtk::DropDown,
+    pub filter_chip_buttons: Vec<(FilterChip, gtk::ToggleButton)>,
+    // Shown while several profiles are selected
+    pub bulk_actions_box: gtk::Box,
+    pub bulk_selection_label: gtk::Label,
+    pub tag_filter_box: gtk::FlowBox,
+    // One chip per tag in use, regenerated with the sidebar rows
+    pub tag_filter_buttons: RefCell<Vec<(String, gtk::ToggleButton)>>,
+    pub sidebar_group_headers: RefCell<HashMap<String, SidebarGroupHeader>>,
+    pub collapsed_groups: RefCell<HashSet<String>>,
+    // Generated from `profile_fields::PROFILE_FIELDS`, with their handler ids
+    pub field_editors: Vec<FieldEditor>,
+    pub preset_dropdown: gtk::DropDown,
+    pub preset_status_label: gtk::Label,
+    pub preset_description_label: gtk::Label,
+    // Shown while conf.toml has no default profile
+    pub add_default_profile_button: gtk::Button,
+    pub default_profile_group: libadwaita::PreferencesGroup,
+    pub inherits_dropdown: gtk::DropDown,
+    pub matching_group: libadwaita::PreferencesGroup,
+    pub patterns_entry: gtk::Entry,
+    pub favorite_switch: gtk::Switch,
+    pub group_row: libadwaita::ActionRow,
+    pub group_entry: gtk::Entry,
+    pub tags_entry: gtk::Entry,
+    pub notes_view: gtk::TextView,
+    pub match_tester_entry: gtk::Entry,
+    pub match_tester_result_label: gtk::Label,
+    pub launch_row: libadwaita::ActionRow,
+    pub desktop_launcher_row: libadwaita::ActionRow,
+    pub gamescope_row: libadwaita::ActionRow,
+    pub calc_base_fps_spin: gtk::SpinButton,
+    pub calc_refresh_spin: gtk::SpinButton,
+    pub calc_results_grid: gtk::Grid,
+    pub calc_suggestion_row: libadwaita::ActionRow,
+    pub calc_apply_button: gtk::Button,
+    pub gamescope_warning_label: gtk::Label,
+    pub remove_launcher_button: gtk::Button,
+    pub steam_app_id_entry: gtk::Entry,
+    pub steam_launch_options_row: libadwaita::ActionRow,
+    pub steam_write_button: gtk::Button,
     pub save_button: gtk::Button,
     pub main_settings_box: gtk::Box,
     // Store SignalHandlerIds to block/unblock signals
-    pub multiplier_dropdown_handler_id: Option<glib::SignalHandlerId>,
-    pub flow_scale_entry_handler_id: Option<glib::SignalHandlerId>,
-    pub performance_mode_switch_handler_id: Option<glib::SignalHandlerId>,
-    pub hdr_mode_switch_handler_id: Option<glib::SignalHandlerId>,
-    pub experimental_present_mode_dropdown_handler_id: Option<glib::SignalHandlerId>,
+    pub preset_dropdown_handler_id: Option<glib::SignalHandlerId>,
+    pub inherits_dropdown_handler_id: Option<glib::SignalHandlerId>,
+    pub patterns_entry_handler_id: Option<glib::SignalHandlerId>,
+    pub favorite_switch_handler_id: Option<glib::SignalHandlerId>,
+    pub group_entry_handler_id: Option<glib::SignalHandlerId>,
+    pub tags_entry_handler_id: Option<glib::SignalHandlerId>,
+    pub notes_buffer_handler_id: Option<glib::SignalHandlerId>,
+    pub steam_app_id_entry_handler_id: Option<glib::SignalHandlerId>,
 }
 
 impl AppState {
     // Saves the current configuration to the TOML file
-    pub fn save_current_config(&self) {
+    pub fn save_current_config(&mut self) {
+        // Inherited values are written out in full so lsfg-vk never needs to know about inheritance
+        flatten_inheritance(&mut self.config, &self.profile_meta);
+        self.profile_meta.prune();
+        sync_launchers(&self.config, &mut self.profile_meta);
+
         if let Err(e) = save_config(&self.config) {
             eprintln!("Failed to save config: {}", e);
             // In a real app, you'd show a user-friendly error dialog here
         }
+        if let Err(e) = save_profile_meta(&self.profile_meta) {
+            eprintln!("Failed to save profile metadata: {}", e);
+        }
+    }
+
+    // Sets the given field values on several profiles at once. Nothing is changed
+    // if any value is rejected. Returns how many profiles actually changed.
+    pub fn apply_bulk_edit(&mut self, indices: &[usize], changes: &[(ProfileField, FieldValue)]) -> Result<usize, String> {
+        let mut edited = Vec::new();
+        for index in indices {
+            let Some(profile) = self.config.game.get(*index) else {
+                return Err("Profile not found".to_string());
+            };
+            let mut updated = profile.clone();
+            for (field, value) in changes {
+                field.set_value(&mut updated, value.clone())
+                    .map_err(|e| format!("{}: {}", field.descriptor().title, e))?;
+            }
+            edited.push((*index, updated));
+        }
+
+        let mut changed = 0;
+        for (index, updated) in edited {
+            let before = std::mem::replace(&mut self.config.game[index], updated);
+            if !changed_fields(&before, &self.config.game[index]).is_empty() {
+                changed += 1;
+            }
+            self.apply_profile_edit(index, &before);
+        }
+        self.save_current_config();
+        Ok(changed)
+    }
+
+    // Replaces the whole config with one from the history. Restored values become
+    // overrides where they differ, so inheritance does not undo the restore.
+    pub fn restore_config(&mut self, restored: Config) {
+        let selected_exe = self.selected_profile_index
+            .and_then(|index| self.config.game.get(index))
+            .map(|profile| profile.exe.clone());
+        let previous = std::mem::replace(&mut self.config, restored);
+        for index in 0..self.config.game.len() {
+            let exe = &self.config.game[index].exe;
+            if let Some(before) = previous.game.iter().find(|profile| profile.exe == *exe).cloned() {
+                self.apply_profile_edit(index, &before);
+            }
+        }
+        self.selected_profile_index = selected_exe
+            .and_then(|exe| self.config.game.iter().position(|profile| profile.exe == exe))
+            .or(if self.config.game.is_empty() { None } else { Some(0) });
+        self.save_current_config();
+    }
+
+    // Puts back one profile from the history, re-adding it if it was removed since
+    pub fn restore_profile(&mut self, restored: GameProfile) {
+        match self.config.game.iter().position(|profile| profile.exe == restored.exe) {
+            Some(index) => {
+                let before = std::mem::replace(&mut self.config.game[index], restored);
+                self.apply_profile_edit(index, &before);
+            }
+            None => self.config.game.push(restored),
+        }
+        self.save_current_config();
+    }
+
+    // Marks fields edited since `before` as overrides and pushes values down to inheriting profiles
+    pub fn apply_profile_edit(&mut self, index: usize, before: &GameProfile) {
+        let Some(profile) = self.config.game.get(index) else {
+            return;
+        };
+        let exe = profile.exe.clone();
+        let changed = changed_fields(before, profile);
+        if !changed.is_empty() {
+            self.profile_meta.entry(&exe).edited_at = Some(unix_time_now());
+        }
+
+        record_profile_edit(&mut self.config, &mut self.profile_meta, &exe, &changed);
+    }
+
+    // Profiles the selected profile may inherit from, in sidebar order
+    pub fn inherit_choices(&self) -> Vec<String> {
+        let selected_exe = self.selected_profile_index
+            .and_then(|index| self.config.game.get(index))
+            .map(|profile| profile.exe.as_str());
+        self.config.game.iter()
+            .map(|profile| profile.exe.clone())
+            .filter(|exe| Some(exe.as_str()) != selected_exe)
+            .collect()
+    }
+
+    // Changes the base of a profile; fields that differ from the new base become overrides
+    pub fn set_profile_base(&mut self, index: usize, base: Option<String>) -> Result<(), String> {
+        let exe = self.config.game.get(index).ok_or("Profile not found")?.exe.clone();
+
+        match base {
+            Some(base_name) => {
+                if would_create_cycle(&self.profile_meta, &exe, &base_name) {
+                    return Err(format!("'{}' already inherits from this profile", base_name));
+                }
+                let base_values = resolve_profile(&self.config, &self.profile_meta, &base_name)
+                    .ok_or("Base profile not found")?;
+                let profile = &self.config.game[index];
+                let overrides = changed_fields(&base_values, profile).into_iter()
+                    .map(|field| field.key().to_string())
+                    .collect();
+                let meta = self.profile_meta.entry(&exe);
+                meta.inherits = Some(base_name);
+                meta.overrides = overrides;
+            }
+            None => {
+                let meta = self.profile_meta.entry(&exe);
+                meta.inherits = None;
+                meta.overrides.clear();
+            }
+        }
+        flatten_inheritance(&mut self.config, &self.profile_meta);
+        Ok(())
+    }
+
+    // Drops a field override so the value follows the base profile again
+    pub fn reset_field_to_inherited(&mut self, index: usize, field: ProfileField) {
+        let Some(exe) = self.config.game.get(index).map(|profile| profile.exe.clone()) else {
+            return;
+        };
+        self.profile_meta.entry(&exe).set_overridden(field, false);
+        flatten_inheritance(&mut self.config, &self.profile_meta);
     }
 
     // Updates the main window UI with data from the currently selected profile
     pub fn update_main_window_from_profile(&self) {
         if let Some(index) = self.selected_profile_index {
             if let Some(profile) = self.config.game.get(index) {
-                let profile_clone = profile.clone();
-                let multiplier_dropdown = self.multiplier_dropdown.clone();
-                let flow_scale_entry = self.flow_scale_entry.clone();
-                let performance_mode_switch = self.performance_mode_switch.clone();
-                let hdr_mode_switch = self.hdr_mode_switch.clone();
-                let experimental_present_mode_dropdown = self.experimental_present_mode_dropdown.clone();
-
                 // Temporarily block signals to prevent re-entrancy
                 with_blocked_signals(self, || {
-                    // Update Multiplier Dropdown
-                    let multiplier_str = match profile_clone.multiplier {
-                        1 => "off",
-                        _ => &profile_clone.multiplier.to_string(),
-                    };
-                    update_dropdown_selection(&multiplier_dropdown, multiplier_str);
+                    for editor in &self.field_editors {
+                        editor.show_value(&editor.field.value(profile));
+                    }
+                });
+            }
+        } else {
+            self.clear_main_window_ui();
+        }
+        self.refresh_profile_indicators();
 
-                    // Update Flow Scale Entry (round to avoid floating point display issues)
-                    let rounded_flow_scale = round_to_2_decimals(profile_clone.flow_scale);
-                    flow_scale_entry.set_text(&format!("{:.2}", rounded_flow_scale));
+        // Only the pinned default profile explains its precedence rules
+        let selected_profile = self.selected_profile_index.and_then(|index| self.config.game.get(index));
+        let is_default = selected_profile.is_some_and(is_default_profile);
+        self.default_profile_group.set_visible(is_default);
 
-                    // Update Performance Mode Switch
-                    performance_mode_switch.set_active(profile_clone.performance_mode);
+        // Which profile the settings belong to, since the list is hidden in the narrow layout
+        let subtitle = match selected_profile {
+            Some(_) if is_default => DEFAULT_PROFILE_LABEL,
+            Some(profile) => profile.exe.as_str(),
+            None => "",
+        };
+        self.content_title.set_subtitle(subtitle);
 
-                    // Update HDR Mode Switch
-                    hdr_mode_switch.set_active(profile_clone.hdr_mode);
+        // Patterns make no sense for the default profile, which is never matched by name
+        self.matching_group.set_visible(!is_default);
+        let patterns = selected_profile
+            .and_then(|profile| self.profile_meta.get(&profile.exe))
+            .map(|meta| meta.patterns.join("; "))
+            .unwrap_or_default();
+        with_blocked_signals(self, || {
+            self.patterns_entry.set_text(&patterns);
+        });
 
-                    // Update Experimental Present Mode Dropdown
-                    update_dropdown_selection(&experimental_present_mode_dropdown, &profile_clone.experimental_present_mode);
-                });
-            }
+        // Organization metadata; the pinned default profile is never grouped
+        let selected_meta = selected_profile.and_then(|profile| self.profile_meta.get(&profile.exe));
+        self.group_row.set_visible(!is_default);
+        with_blocked_signals(self, || {
+            self.favorite_switch.set_active(selected_meta.is_some_and(|meta| meta.favorite));
+            self.group_entry.set_text(selected_meta.and_then(|meta| meta.group.as_deref()).unwrap_or_default());
+            self.tags_entry.set_text(&selected_meta.map(|meta| meta.tags.join(", ")).unwrap_or_default());
+            self.notes_view.buffer().set_text(selected_meta.map_or("", |meta| meta.notes.as_str()));
+        });
+
+        // Summarise how the game is launched
+        let launch_command = selected_meta
+            .and_then(|meta| meta.launch.as_ref())
+            .map(|launch| launch.command.clone())
+            .unwrap_or_else(|| "Not configured".to_string());
+        self.launch_row.set_subtitle(&launch_command);
+
+        let launcher = selected_meta.and_then(|meta| meta.launcher.as_ref());
+        let launcher_summary = launcher
+            .map(|files| files.paths().join("\n"))
+            .unwrap_or_else(|| "Not created".to_string());
+        self.desktop_launcher_row.set_subtitle(&launcher_summary);
+        self.remove_launcher_button.set_visible(launcher.is_some());
+
+        // Steam launch options follow the profile name and its extra environment
+        let app_id = selected_meta.and_then(|meta| meta.steam_app_id.clone()).unwrap_or_default();
+        with_blocked_signals(self, || {
+            self.steam_app_id_entry.set_text(&app_id);
+        });
+        self.refresh_steam_launch_options();
+    }
+
+    // Regenerates the Steam launch option string for the selected profile
+    pub fn refresh_steam_launch_options(&self) {
+        let selected_profile = self.selected_profile_index.and_then(|index| self.config.game.get(index));
+        let launch_options = selected_profile
+            .map(|profile| {
+                let meta = self.profile_meta.get(&profile.exe);
+                let launch = meta.and_then(|meta| meta.launch.as_ref());
+                generate_launch_options(&profile.exe, launch, &gamescope_prefix(meta, profile))
+            })
+            .unwrap_or_default();
+        self.steam_launch_options_row.set_subtitle(&launch_options);
+        self.steam_write_button.set_sensitive(is_valid_app_id(self.steam_app_id_entry.text().trim()));
+    }
+
+    // Evaluates the "which profile matches?" box and highlights the winning sidebar row
+    pub fn refresh_match_tester(&self) {
+        let process = self.match_tester_entry.text().to_string();
+        let result = if process.trim().is_empty() {
+            None
         } else {
-            self.clear_main_window_ui();
+            find_matching_profile(&self.config, &self.profile_meta, &process)
+        };
+
+        match &result {
+            Some(result) => self.match_tester_result_label.set_text(&result.explain(&self.config)),
+            None => self.match_tester_result_label.set_text("No profile applies to this process."),
+        }
+        self.match_tester_result_label.set_visible(!process.trim().is_empty());
+
+        let winning_index = result.map(|result| result.profile_index);
+        for (index, row) in self.sidebar_rows.borrow().iter().enumerate() {
+            if winning_index == Some(index) {
+                row.add_css_class("match-highlight");
+            } else {
+                row.remove_css_class("match-highlight");
+            }
         }
     }
 
-    // Clears the main window UI when no profile is selected
-    fn clear_main_window_ui(&self) {
-        self.multiplier_dropdown.set_selected(0);
-        self.flow_scale_entry.set_text("");
-        self.performance_mode_switch.set_active(false);
-        self.hdr_mode_switch.set_active(false);
-        self.experimental_present_mode_dropdown.set_selected(0);
+    // Refreshes every marker derived from the selected profile's values
+    pub fn refresh_profile_indicators(&self) {
+        // Filters and sort order depend on the values too
+        self.apply_sidebar_view();
+        self.refresh_preset_indicator();
+        self.refresh_inheritance_indicators();
+        self.refresh_gamescope_summary();
+        self.refresh_frame_calculator();
     }
 
-    // Populates sidebar with optional app_state for button handlers
-    pub fn populate_sidebar_with_handlers(&self, app_state: Option<Rc<RefCell<AppState>>>) {
-        // Clear existing rows
-        while let Some(child) = self.sidebar_list_box.first_child() {
-            self.sidebar_list_box.remove(&child);
+    // Fills the calculator table and suggests a present mode for the profile's multiplier
+    pub fn refresh_frame_calculator(&self) {
+        while let Some(child) = self.calc_results_grid.first_child() {
+            self.calc_results_grid.remove(&child);
+        }
+
+        let base_fps = self.calc_base_fps_spin.value();
+        let refresh_rate = self.calc_refresh_spin.value();
+        let estimates = estimate_frame_rates(base_fps, refresh_rate, &CALCULATOR_MULTIPLIERS);
+        let recommended = recommended_multiplier(&estimates);
+        let current_multiplier = self.selected_profile_index
+            .and_then(|index| self.config.game.get(index))
+            .map(|profile| profile.multiplier);
+
+        for (column, heading) in ["Multiplier", "Output", "Added Latency", ""].iter().enumerate() {
+            let label = gtk::Label::builder().label(*heading).xalign(0.0).css_classes(["heading"]).build();
+            self.calc_results_grid.attach(&label, column as i32, 0, 1, 1);
+        }
+        for (row, estimate) in estimates.iter().enumerate() {
+            let status = if estimate.exceeds_refresh {
+                "Exceeds refresh rate"
+            } else if Some(estimate.multiplier) == recommended {
+                "Recommended"
+            } else {
+                ""
+            };
+            let cells = [
+                format!("×{}", estimate.multiplier),
+                format!("{:.0} FPS", estimate.output_fps),
+                format!("+{:.1} ms", estimate.added_latency_ms),
+                status.to_string(),
+            ];
+            for (column, text) in cells.iter().enumerate() {
+                let label = gtk::Label::builder().label(text.as_str()).xalign(0.0).build();
+                if estimate.exceeds_refresh {
+                    label.add_css_class("warning");
+                }
+                if Some(estimate.multiplier) == current_multiplier {
+                    label.add_css_class("heading");
+                }
+                self.calc_results_grid.attach(&label, column as i32, row as i32 + 1, 1, 1);
+            }
+        }
+
+        let (mode, reason) = self.present_mode_suggestion();
+        self.calc_suggestion_row.set_title(&format!("Suggested Present Mode: {}", mode));
+        self.calc_suggestion_row.set_subtitle(reason);
+        let already_applied = self.selected_profile_index
+            .and_then(|index| self.config.game.get(index))
+            .is_some_and(|profile| profile.experimental_present_mode == mode);
+        self.calc_apply_button.set_sensitive(!already_applied);
+    }
+
+    // The present mode suggested for the selected profile's multiplier, with the reason
+    pub fn present_mode_suggestion(&self) -> (&'static str, &'static str) {
+        // Frame generation off (multiplier 1) means the output is just the base frame rate
+        let multiplier = self.selected_profile_index
+            .and_then(|index| self.config.game.get(index))
+            .map(|profile| profile.multiplier.max(1))
+            .unwrap_or(1);
+        let output_fps = self.calc_base_fps_spin.value() * multiplier as f64;
+        suggest_present_mode(output_fps, self.calc_refresh_spin.value())
+    }
+
+    // Shows the gamescope prefix and any conflicts with the lsfg-vk settings
+    fn refresh_gamescope_summary(&self) {
+        let profile = self.selected_profile_index.and_then(|index| self.config.game.get(index));
+        let settings = profile
+            .and_then(|profile| self.profile_meta.get(&profile.exe))
+            .and_then(|meta| meta.gamescope.as_ref())
+            .filter(|settings| settings.enabled);
+
+        let (summary, conflicts) = match (profile, settings) {
+            (Some(profile), Some(settings)) => (settings.build_args(profile).join(" "), settings.conflicts(profile)),
+            _ => ("Off".to_string(), Vec::new()),
+        };
+        self.gamescope_row.set_subtitle(&summary);
+        self.gamescope_warning_label.set_text(&conflicts.join("\n"));
+        self.gamescope_warning_label.set_visible(!conflicts.is_empty());
+        // The Steam launch options include the gamescope prefix
+        self.refresh_steam_launch_options();
+    }
+
+    // Dims inherited fields, shows reset buttons for overrides and syncs the base dropdown
+    fn refresh_inheritance_indicators(&self) {
+        let profile = self.selected_profile_index.and_then(|index| self.config.game.get(index));
+        let choices = self.inherit_choices();
+
+        let mut labels = vec!["Nothing".to_string()];
+        labels.extend(choices.iter().map(|exe| {
+            if exe == DEFAULT_PROFILE_EXE { DEFAULT_PROFILE_LABEL.to_string() } else { exe.clone() }
+        }));
+        let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let base = profile.and_then(|p| self.profile_meta.inherits(&p.exe));
+        let selected = base
+            .and_then(|base| choices.iter().position(|exe| exe == base))
+            .map_or(0, |position| position as u32 + 1);
+
+        with_blocked_signals(self, || {
+            self.inherits_dropdown.set_model(Some(&gtk::StringList::new(&label_refs)));
+            self.inherits_dropdown.set_selected(selected);
+        });
+
+        for editor in &self.field_editors {
+            let inherited = profile.is_some_and(|p| self.profile_meta.is_inherited(&p.exe, editor.field));
+            editor.set_inherited(inherited, base.is_some());
         }
+    }
 
-        let mut row_to_select: Option<ListBoxRow> = None;
+    // Shows which built-in preset the selected profile matches, or marks it as customized
+    fn refresh_preset_indicator(&self) {
+        let profile = self.selected_profile_index.and_then(|index| self.config.game.get(index));
+        let matching_preset = profile.and_then(find_matching_preset);
 
-        for (i, profile) in self.config.game.iter().enumerate() {
-            let row = self.create_profile_row(profile, i, &app_state);
-            self.sidebar_list_box.append(&row);
+        with_blocked_signals(self, || {
+            self.preset_dropdown.set_selected(matching_preset.map_or(0, |index| index as u32 + 1));
+        });
+
+        match (profile, matching_preset.and_then(|index| preset_catalog().preset.get(index))) {
+            (None, _) => {
+                self.preset_status_label.set_text("");
+                self.preset_description_label.set_text("");
+            }
+            (Some(_), Some(preset)) => {
+                self.preset_status_label.set_text("Matches preset");
+                self.preset_status_label.set_css_classes(css_classes::PRESET_BADGE_MATCHED);
+                self.preset_description_label.set_text(&preset.description);
+            }
+            (Some(_), None) => {
+                self.preset_status_label.set_text("Customized");
+                self.preset_status_label.set_css_classes(css_classes::PRESET_BADGE_CUSTOM);
+                self.preset_description_label.set_text("These settings do not match any built-in preset. Pick one to start from a known trade-off.");
+            }
+        }
+    }
 
-            // Mark the row to be selected later
-            if self.selected_profile_index == Some(i) {
-                row_to_select = Some(row.clone());
+    // Clears the main window UI when no profile is selected
+    fn clear_main_window_ui(&self) {
+        with_blocked_signals(self, || {
+            for editor in &self.field_editors {
+                editor.clear();
             }
+        });
+    }
+
+    // Populates sidebar with optional app_state for button handlers
+    pub fn populate_sidebar_with_handlers(&self, app_state: Option<Rc<RefCell<AppState>>>) {
+        let rows: Vec<ListBoxRow> = self.config.game.iter()
+            .enumerate()
+            .map(|(i, profile)| self.create_profile_row(profile, i, &app_state))
+            .collect();
+        *self.sidebar_rows.borrow_mut() = rows;
+        self.add_default_profile_button.set_visible(!self.config.game.iter().any(is_default_profile));
+        if let Some(app_state) = &app_state {
+            self.refresh_tag_filters(app_state);
         }
+        self.apply_sidebar_view();
+
+        // Re-apply the match tester highlight to the fresh rows
+        self.refresh_match_tester();
 
         // Perform selection in a separate idle callback
-        if let Some(row) = row_to_select {
+        if let Some(row) = self.selected_profile_index.and_then(|index| self.sidebar_rows.borrow().get(index).cloned()) {
             let list_box_clone = self.sidebar_list_box.clone();
             glib::idle_add_local(move || {
-                list_box_clone.select_row(Some(&row));
+                if row.parent().is_some() {
+                    list_box_clone.unselect_all();
+                    list_box_clone.select_row(Some(&row));
+                }
                 glib::ControlFlow::Break
             });
         }
     }
 
-    // Creates a single profile row with edit and remove buttons
+    // The search text and filter chips currently set in the sidebar
+    pub fn sidebar_filter(&self) -> SidebarFilter {
+        SidebarFilter {
+            query: self.sidebar_search_entry.text().to_string(),
+            chips: self.filter_chip_buttons.iter()
+                .filter(|(_, button)| button.is_active())
+                .map(|(chip, _)| *chip)
+                .collect(),
+            tags: self.tag_filter_buttons.borrow().iter()
+                .filter(|(_, button)| button.is_active())
+                .map(|(tag, _)| tag.clone())
+                .collect(),
+        }
+    }
+
+    // Rebuilds the tag chips from the tags in use, keeping active ones active
+    pub fn refresh_tag_filters(&self, app_state: &Rc<RefCell<AppState>>) {
+        let tags = all_tags(&self.config, &self.profile_meta);
+        let mut buttons = self.tag_filter_buttons.borrow_mut();
+        if buttons.iter().map(|(tag, _)| tag).eq(tags.iter()) {
+            return;
+        }
+
+        let active: Vec<String> = buttons.iter()
+            .filter(|(_, button)| button.is_active())
+            .map(|(tag, _)| tag.clone())
+            .collect();
+        while let Some(child) = self.tag_filter_box.first_child() {
+            self.tag_filter_box.remove(&child);
+        }
+        *buttons = tags.into_iter()
+            .map(|tag| {
+                let button = gtk::ToggleButton::builder()
+                    .label(format!("#{}", tag))
+                    .tooltip_text(format!("Profiles tagged '{}'", tag))
+                    .active(active.contains(&tag))
+                    .css_classes(["filter-chip"])
+                    .build();
+                let app_state_clone = app_state.clone();
+                button.connect_toggled(move |_| {
+                    app_state_clone.borrow().apply_sidebar_view();
+                });
+                self.tag_filter_box.insert(&button, -1);
+                (tag, button)
+            })
+            .collect();
+        self.tag_filter_box.set_visible(!buttons.is_empty());
+    }
+
+    pub fn sidebar_sort_mode(&self) -> SortMode {
+        SORT_MODES.get(self.sidebar_sort_dropdown.selected() as usize)
+            .map_or(SortMode::FileOrder, |(mode, _)| *mode)
+    }
+
+    // Shows the rows that pass the search and filters, in sort order and under their group headers
+    pub fn apply_sidebar_view(&self) {
+        let items = self.sidebar_items();
+        let rows = self.sidebar_rows.borrow();
+        let shown: Vec<ListBoxRow> = items.iter()
+            .filter_map(|item| match item {
+                SidebarItem::Group { name, count, collapsed } => Some(self.group_header_row(name, *count, *collapsed)),
+                SidebarItem::Profile(index) => rows.get(*index).cloned(),
+            })
+            .collect();
+        self.sidebar_group_headers.borrow_mut().retain(|name, _| {
+            items.iter().any(|item| matches!(item, SidebarItem::Group { name: group, .. } if group == name))
+        });
+
+        // Leave the list alone if nothing moved, so focus and scrolling are kept
+        let current: Vec<ListBoxRow> = (0..)
+            .map_while(|position| self.sidebar_list_box.row_at_index(position))
+            .collect();
+        if current == shown {
+            return;
+        }
+
+        for row in &current {
+            self.sidebar_list_box.remove(row);
+        }
+        for row in &shown {
+            self.sidebar_list_box.append(row);
+        }
+        if let Some(row) = self.selected_profile_index.and_then(|index| rows.get(index)) {
+            if row.parent().is_some() && !row.is_selected() {
+                self.sidebar_list_box.select_row(Some(row));
+            }
+        }
+    }
+
+    fn sidebar_items(&self) -> Vec<SidebarItem> {
+        let order = sidebar_order(&self.config, &self.profile_meta, &self.sidebar_filter(), self.sidebar_sort_mode());
+        sidebar_items(&self.config, &self.profile_meta, &order, &self.collapsed_groups.borrow())
+    }
+
+    // Config indices of the profiles currently listed in the sidebar, top to bottom
+    pub fn visible_profile_order(&self) -> Vec<usize> {
+        self.sidebar_items().into_iter()
+            .filter_map(|item| match item {
+                SidebarItem::Profile(index) => Some(index),
+                SidebarItem::Group { .. } => None,
+            })
+            .collect()
+    }
+
+    // Returns the header row of a group, creating it on first use
+    fn group_header_row(&self, name: &str, count: usize, collapsed: bool) -> ListBoxRow {
+        let mut headers = self.sidebar_group_headers.borrow_mut();
+        let header = headers.entry(name.to_string()).or_insert_with(|| {
+            let row = ListBoxRow::builder()
+                .selectable(false)
+                .css_classes(["sidebar-group-header"])
+                .build();
+            let row_box = LayoutFactory::create_horizontal_box_with_margins(8);
+            let expander_icon = gtk::Image::new();
+            let label = Label::builder()
+                .halign(gtk::Align::Start)
+                .hexpand(true)
+                .css_classes(["heading"])
+                .build();
+            row_box.append(&expander_icon);
+            row_box.append(&label);
+            row.set_child(Some(&row_box));
+            SidebarGroupHeader { row, label, expander_icon }
+        });
+        header.label.set_label(&format!("{} ({})", name, count));
+        header.expander_icon.set_icon_name(Some(if collapsed { "pan-end-symbolic" } else { "pan-down-symbolic" }));
+        header.row.set_tooltip_text(Some(if collapsed { "Expand group" } else { "Collapse group" }));
+        header.row.clone()
+    }
+
+    // The group a sidebar header row belongs to
+    pub fn group_of_header_row(&self, row: &ListBoxRow) -> Option<String> {
+        self.sidebar_group_headers.borrow().iter()
+            .find(|(_, header)| header.row == *row)
+            .map(|(name, _)| name.clone())
+    }
+
+    // Shows or hides the profiles of a sidebar group
+    pub fn toggle_group_collapsed(&self, group: &str) {
+        let mut collapsed = self.collapsed_groups.borrow_mut();
+        if !collapsed.remove(group) {
+            collapsed.insert(group.to_string());
+        }
+        drop(collapsed);
+        self.apply_sidebar_view();
+    }
+
+    // Adds the default profile on request, selects it and saves
+    pub fn add_default_profile(&mut self) {
+        if !crate::default_profile::add_default_profile(&mut self.config) {
+            return;
+        }
+        self.selected_profile_index = Some(0);
+        self.save_current_config();
+    }
+
+    // Selects the profile listed before (-1) or after (1) the selected one
+    pub fn select_adjacent_profile(&self, offset: isize) {
+        let order = self.visible_profile_order();
+        let position = self.selected_profile_index
+            .and_then(|index| order.iter().position(|i| *i == index));
+        let target = match position {
+            Some(position) => position.checked_add_signed(offset).and_then(|position| order.get(position)),
+            None => order.first(),
+        };
+        let Some(row) = target.and_then(|index| self.sidebar_rows.borrow().get(*index).cloned()) else {
+            return;
+        };
+        self.sidebar_list_box.unselect_all();
+        self.sidebar_list_box.select_row(Some(&row));
+        row.grab_focus();
+    }
+
+    // In the narrow layout, navigates from the profile list to the settings
+    pub fn show_profile_pane(&self) {
+        if self.split_view.is_collapsed() {
+            self.split_view.set_show_content(true);
+        }
+    }
+
+    // Fixes the sidebar at a width in pixels instead of a fraction of the window
+    pub fn set_sidebar_width(&self, width: i32) {
+        let width = width.clamp(MIN_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH);
+        self.split_view.set_min_sidebar_width(width as f64);
+        self.split_view.set_max_sidebar_width(width as f64);
+        self.sidebar_width.set(Some(width));
+    }
+
+    // How the window and sidebar are left, to be restored on the next launch
+    pub fn ui_state(&self) -> UiState {
+        let (width, height) = self.main_window.default_size();
+        let mut collapsed_groups: Vec<String> = self.collapsed_groups.borrow().iter().cloned().collect();
+        collapsed_groups.sort();
+        let filter = self.sidebar_filter();
+        UiState {
+            window_width: (width > 0).then_some(width),
+            window_height: (height > 0).then_some(height),
+            maximized: self.main_window.is_maximized(),
+            sidebar_width: self.sidebar_width.get(),
+            selected_profile: self.selected_profile_index
+                .and_then(|index| self.config.game.get(index))
+                .map(|profile| profile.exe.clone()),
+            sort_mode: Some(self.sidebar_sort_mode().key().to_string()),
+            filter_chips: filter.chips.iter().map(|chip| chip.key().to_string()).collect(),
+            filter_tags: filter.tags,
+            collapsed_groups,
+        }
+    }
+
+    // Puts back the window size, sidebar width, sort and filter chips of a previous session.
+    // Tag chips and the selection follow once the sidebar is populated.
+    pub fn restore_ui_state(&self, ui_state: &UiState) {
+        if let (Some(width), Some(height)) = (ui_state.window_width, ui_state.window_height) {
+            self.main_window.set_default_size(width, height);
+        }
+        if ui_state.maximized {
+            self.main_window.maximize();
+        }
+        if let Some(width) = ui_state.sidebar_width {
+            self.set_sidebar_width(width);
+        }
+        if let Some(position) = ui_state.sort_mode()
+            .and_then(|sort| SORT_MODES.iter().position(|(mode, _)| *mode == sort))
+        {
+            self.sidebar_sort_dropdown.set_selected(position as u32);
+        }
+        let chips = ui_state.filter_chips();
+        for (chip, button) in &self.filter_chip_buttons {
+            button.set_active(chips.contains(chip));
+        }
+        *self.collapsed_groups.borrow_mut() = ui_state.collapsed_groups.iter().cloned().collect();
+    }
+
+    // Moves a profile to another position in conf.toml; the selection follows the selected profile
+    pub fn move_profile(&mut self, from: usize, to: usize) -> Result<(), String> {
+        let (Some(moved), Some(target)) = (self.config.game.get(from), self.config.game.get(to)) else {
+            return Err("Profile not found".to_string());
+        };
+        if is_default_profile(moved) || is_default_profile(target) {
+            return Err("The default profile stays at the top".to_string());
+        }
+        if from == to {
+            return Ok(());
+        }
+
+        // Dropped next to a profile of another group, the moved profile joins that group
+        let target_group = self.profile_meta.get(&target.exe).and_then(|meta| meta.group.clone());
+        let moved_exe = moved.exe.clone();
+        if self.profile_meta.get(&moved_exe).and_then(|meta| meta.group.clone()) != target_group {
+            self.profile_meta.entry(&moved_exe).group = target_group;
+        }
+
+        let profile = self.config.game.remove(from);
+        self.config.game.insert(to, profile);
+        self.selected_profile_index = self.selected_profile_index.map(|index| {
+            if index == from {
+                to
+            } else if from < index && index <= to {
+                index - 1
+            } else if to <= index && index < from {
+                index + 1
+            } else {
+                index
+            }
+        });
+        self.save_current_config();
+        Ok(())
+    }
+
+    // Config indices of every selected sidebar row, in config order
+    pub fn selected_profile_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.sidebar_list_box.selected_rows().iter()
+            .filter_map(|row| self.profile_index_of_row(row))
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    // Offers the bulk actions while more than one profile is selected
+    pub fn refresh_bulk_actions(&self) {
+        let count = self.selected_profile_indices().len();
+        self.bulk_actions_box.set_visible(count > 1);
+        self.bulk_selection_label.set_label(&format!("{} profiles selected", count));
+        crate::actions::refresh_action_states(self);
+    }
+
+    // The config index of a sidebar row
+    pub fn profile_index_of_row(&self, row: &ListBoxRow) -> Option<usize> {
+        self.sidebar_rows.borrow().iter().position(|r| r == row)
+    }
+
+    // Creates a single profile row with edit, duplicate and remove buttons
     fn create_profile_row(
         &self,
         profile: &crate::config::GameProfile,
@@ -128,14 +835,30 @@ impl AppState {
     ) -> ListBoxRow {
         let row = ListBoxRow::new();
         let row_box = LayoutFactory::create_horizontal_box_with_margins(8);
+        let is_default = is_default_profile(profile);
 
         // Profile name label
         let label = Label::builder()
-            .label(&profile.exe)
+            .label(if is_default { DEFAULT_PROFILE_LABEL } else { &profile.exe })
             .halign(gtk::Align::Start)
             .hexpand(true)
             .build();
 
+        if self.profile_meta.get(&profile.exe).is_some_and(|meta| meta.favorite) {
+            let favorite_icon = gtk::Image::builder()
+                .icon_name("starred-symbolic")
+                .tooltip_text("Favorite")
+                .build();
+            row_box.append(&favorite_icon);
+        }
+
+        // The default profile is pinned: it gets a marker and cannot be renamed or removed
+        if is_default {
+            let pin_icon = gtk::Image::from_icon_name("view-pin-symbolic");
+            row_box.append(&pin_icon);
+            row.set_tooltip_text(Some("Applied to games started with LSFG_PROCESS=lsfg-default"));
+        }
+
         // Edit button
         let edit_button = Button::builder()
             .icon_name("document-edit-symbolic")
@@ -143,6 +866,20 @@ impl AppState {
             .tooltip_text("Edit profile name")
             .build();
 
+        // Launch button
+        let launch_button = Button::builder()
+            .icon_name("media-playback-start-symbolic")
+            .css_classes(css_classes::CIRCULAR_BUTTON)
+            .tooltip_text("Launch game with lsfg-vk")
+            .build();
+
+        // Duplicate button
+        let duplicate_button = Button::builder()
+            .icon_name("edit-copy-symbolic")
+            .css_classes(css_classes::CIRCULAR_BUTTON)
+            .tooltip_text("Duplicate profile")
+            .build();
+
         // Remove button
         let remove_button = Button::builder()
             .icon_name("user-trash-symbolic")
@@ -151,8 +888,14 @@ impl AppState {
             .build();
 
         row_box.append(&label);
-        row_box.append(&edit_button);
-        row_box.append(&remove_button);
+        row_box.append(&launch_button);
+        if !is_default {
+            row_box.append(&edit_button);
+        }
+        row_box.append(&duplicate_button);
+        if !is_default {
+            row_box.append(&remove_button);
+        }
 
         // Connect button handlers if app_state is available
         if let Some(app_state_ref) = app_state {
@@ -161,10 +904,50 @@ impl AppState {
                 show_edit_profile_dialog(app_state_clone.clone(), index);
             });
 
+            let app_state_clone = app_state_ref.clone();
+            launch_button.connect_clicked(move |_| {
+                launch_profile(app_state_clone.clone(), index);
+            });
+
+            let app_state_clone = app_state_ref.clone();
+            duplicate_button.connect_clicked(move |_| {
+                show_duplicate_profile_dialog(app_state_clone.clone(), index);
+            });
+
             let app_state_clone = app_state_ref.clone();
             remove_button.connect_clicked(move |_| {
                 show_remove_profile_dialog(app_state_clone.clone(), index);
             });
+
+            // Drag a row onto another to move it there; the default profile stays pinned
+            if !is_default {
+                let drag_source = gtk::DragSource::new();
+                drag_source.set_actions(gtk::gdk::DragAction::MOVE);
+                drag_source.set_content(Some(&gtk::gdk::ContentProvider::for_value(&(index as u32).to_value())));
+                let row_clone = row.clone();
+                drag_source.connect_drag_begin(move |source, _| {
+                    source.set_icon(Some(&gtk::WidgetPaintable::new(Some(&row_clone))), 0, 0);
+                });
+                row.add_controller(drag_source);
+
+                let drop_target = gtk::DropTarget::new(glib::Type::U32, gtk::gdk::DragAction::MOVE);
+                let app_state_clone = app_state_ref.clone();
+                drop_target.connect_drop(move |_, value, _, _| {
+                    let Ok(from) = value.get::<u32>() else {
+                        return false;
+                    };
+                    match reorder_profile(&app_state_clone, from as usize, index) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            let main_settings_box = app_state_clone.borrow().main_settings_box.clone();
+                            FeedbackUtils::show_temporary_feedback(&main_settings_box, &e, 3);
+                            false
+                        }
+                    }
+                });
+                row.add_controller(drop_target);
+                row.add_css_class("reorderable-row");
+            }
         }
 
         row.set_child(Some(&row_box));
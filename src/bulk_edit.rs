@@ -0,0 +1,291 @@
+//! Editing, exporting and removing several profiles at once
+//! The bulk editor is generated from the same field schema as the profile
+//! editor. Fields the selected profiles disagree on start out as mixed, and
+//! only the fields the user actually changes are written to every profile.
+
+use gtk::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::app_state::AppState;
+use crate::config::{Config, GameProfile, OrderedGlobalConfig};
+use crate::default_profile::is_default_profile;
+use crate::gpu::{enumerate_gpus, sysfs_root};
+use crate::profile_editor::{build_field_editors, EditorInput, FieldEditor};
+use crate::profile_fields::{FieldGroup, FieldValue, ProfileField};
+use crate::profile_manager::{ask_custom_value, remove_profiles, show_profile_error};
+use crate::ui_components::{DialogFactory, FeedbackUtils, LayoutFactory, PreferencesFactory};
+
+/// The value all profiles share for a field, or `None` if they differ
+pub fn common_value(profiles: &[&GameProfile], field: ProfileField) -> Option<FieldValue> {
+    let mut values = profiles.iter().map(|profile| field.value(profile));
+    let first = values.next()?;
+    values.all(|value| value == first).then_some(first)
+}
+
+/// Writes profiles as `[[game]]` entries, in a file lsfg-vk could read as its conf.toml
+pub fn export_profiles(config: &Config, indices: &[usize], path: &Path) -> Result<(), String> {
+    let export = Config {
+        version: config.version,
+        ordered_global: OrderedGlobalConfig::default(),
+        game: indices.iter().filter_map(|index| config.game.get(*index).cloned()).collect(),
+    };
+    let toml_string = toml::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    fs::write(path, toml_string).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    println!("Exported {} profiles to: {:?}", export.game.len(), path);
+    Ok(())
+}
+
+/// Names of the given profiles for a confirmation message, shortened for long selections
+fn profile_names(config: &Config, indices: &[usize]) -> String {
+    const SHOWN: usize = 8;
+    let names: Vec<&str> = indices.iter()
+        .filter_map(|index| config.game.get(*index))
+        .map(|profile| profile.exe.as_str())
+        .collect();
+    if names.len() <= SHOWN {
+        return names.join(", ");
+    }
+    format!("{} and {} more", names[..SHOWN].join(", "), names.len() - SHOWN)
+}
+
+struct BulkEditor {
+    editors: Vec<FieldEditor>,
+    // Per editor: the value every selected profile shares, if any, and the first profile's value
+    initial: Vec<(Option<FieldValue>, FieldValue)>,
+    changes: RefCell<Vec<(ProfileField, FieldValue)>>,
+    // Set while values are shown from code, so they are not taken for edits
+    restoring: Cell<bool>,
+}
+
+impl BulkEditor {
+    fn changed_value(&self, field: ProfileField) -> Option<FieldValue> {
+        self.changes.borrow().iter()
+            .find(|(changed, _)| *changed == field)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn record(&self, position: usize, value: FieldValue) {
+        let editor = &self.editors[position];
+        let mut changes = self.changes.borrow_mut();
+        changes.retain(|(field, _)| *field != editor.field);
+        changes.push((editor.field, value));
+        editor.clear_mixed();
+        editor.reset_button.set_visible(true);
+    }
+
+    fn forget(&self, position: usize) {
+        let field = self.editors[position].field;
+        self.changes.borrow_mut().retain(|(changed, _)| *changed != field);
+        self.show(position);
+    }
+
+    // Shows a field's pending change, or what it was before the bulk edit
+    fn show(&self, position: usize) {
+        let editor = &self.editors[position];
+        let changed = self.changed_value(editor.field);
+        self.restoring.set(true);
+        match (&changed, &self.initial[position]) {
+            (Some(value), _) | (None, (Some(value), _)) => {
+                editor.clear_mixed();
+                editor.show_value(value);
+            }
+            (None, (None, sample)) => editor.show_mixed(sample),
+        }
+        self.restoring.set(false);
+        editor.reset_button.set_visible(changed.is_some());
+    }
+}
+
+/// Shows the editor that changes fields of every selected profile
+pub fn show_bulk_edit_dialog(app_state: Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    let indices = state.selected_profile_indices();
+    if indices.is_empty() {
+        return;
+    }
+    let main_window = state.main_window.clone();
+    let profiles: Vec<GameProfile> = indices.iter().map(|index| state.config.game[*index].clone()).collect();
+    drop(state);
+
+    let dialog = DialogFactory::create_message_dialog(
+        &main_window,
+        "Edit Profiles",
+        &format!(
+            "Changes apply to all {} selected profiles. Fields you leave alone keep each profile's own value.",
+            indices.len()
+        ),
+        gtk::MessageType::Question,
+    );
+    dialog.set_default_size(560, -1);
+
+    let groups = [
+        (FieldGroup::FrameGeneration, PreferencesFactory::create_preferences_group("Frame Generation", None)),
+        (FieldGroup::Misc, PreferencesFactory::create_preferences_group("Misc", None)),
+    ];
+    let content = LayoutFactory::create_vertical_box(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    for (_, group) in &groups {
+        content.append(group);
+    }
+    dialog.content_area().append(&content);
+
+    let editors = build_field_editors(&groups, &enumerate_gpus(&sysfs_root()));
+    let profile_refs: Vec<&GameProfile> = profiles.iter().collect();
+    let initial = editors.iter()
+        .map(|editor| (common_value(&profile_refs, editor.field), editor.field.value(&profiles[0])))
+        .collect();
+    let bulk = Rc::new(BulkEditor {
+        editors,
+        initial,
+        changes: RefCell::new(Vec::new()),
+        restoring: Cell::new(false),
+    });
+
+    for (position, editor) in bulk.editors.iter().enumerate() {
+        bulk.show(position);
+        editor.reset_button.set_tooltip_text(Some("Keep each profile's own value"));
+
+        let bulk_clone = bulk.clone();
+        editor.reset_button.connect_clicked(move |_| bulk_clone.forget(position));
+
+        let bulk_clone = bulk.clone();
+        let main_window_clone = main_window.clone();
+        editor.widget.connect_changed(move |input| {
+            if bulk_clone.restoring.get() {
+                return;
+            }
+            match input {
+                EditorInput::Value(value) => bulk_clone.record(position, value),
+                EditorInput::CustomRequested => {
+                    let editor = &bulk_clone.editors[position];
+                    let current = bulk_clone.changed_value(editor.field)
+                        .or_else(|| bulk_clone.initial[position].0.clone());
+                    let bulk_for_dialog = bulk_clone.clone();
+                    ask_custom_value(&main_window_clone, editor.field, current.as_ref(), move |value| {
+                        if let Some(value) = value {
+                            bulk_for_dialog.record(position, FieldValue::Choice(Some(value)));
+                        }
+                        bulk_for_dialog.show(position);
+                    });
+                }
+            }
+        });
+    }
+
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    dialog.add_button("Apply", gtk::ResponseType::Other(1));
+    dialog.set_default_response(gtk::ResponseType::Other(1));
+
+    dialog.connect_response(move |d, response| {
+        let changes = bulk.changes.borrow().clone();
+        if response != gtk::ResponseType::Other(1) || changes.is_empty() {
+            d.close();
+            return;
+        }
+
+        let mut state = app_state.borrow_mut();
+        match state.apply_bulk_edit(&indices, &changes) {
+            Ok(changed) => {
+                state.populate_sidebar_with_handlers(Some(app_state.clone()));
+                let main_settings_box = state.main_settings_box.clone();
+                drop(state);
+                app_state.borrow().update_main_window_from_profile();
+                FeedbackUtils::show_temporary_feedback(&main_settings_box, &format!("Updated {} profiles", changed), 2);
+                d.close();
+            }
+            Err(e) => {
+                drop(state);
+                show_profile_error(d, &e);
+            }
+        }
+    });
+
+    dialog.present();
+}
+
+/// Asks once before removing every selected profile; the default profile is kept
+pub fn show_bulk_delete_dialog(app_state: Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let indices: Vec<usize> = state.selected_profile_indices().into_iter()
+        .filter(|index| !is_default_profile(&state.config.game[*index]))
+        .collect();
+    let names = profile_names(&state.config, &indices);
+    drop(state);
+    if indices.is_empty() {
+        return;
+    }
+
+    let dialog = DialogFactory::create_confirmation_dialog(
+        &main_window,
+        "Remove Profiles",
+        &format!("Are you sure you want to remove these {} profiles?\n\n{}", indices.len(), names),
+        "Remove",
+    );
+
+    dialog.connect_response(move |d, response| {
+        if response == gtk::ResponseType::Other(1) {
+            remove_profiles(&app_state, &indices);
+        }
+        d.close();
+    });
+
+    dialog.present();
+}
+
+/// Asks where to export the selected profiles and writes them there
+pub fn show_bulk_export_dialog(app_state: Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let indices = state.selected_profile_indices();
+    let names = profile_names(&state.config, &indices);
+    drop(state);
+    if indices.is_empty() {
+        return;
+    }
+
+    let default_path = dirs::home_dir()
+        .unwrap_or_default()
+        .join("lsfg-vk-profiles.toml");
+    let (dialog, entry) = DialogFactory::create_input_dialog(
+        &main_window,
+        "Export Profiles",
+        &format!(
+            "Write these {} profiles as [[game]] entries to a TOML file? An existing file is replaced.\n\n{}",
+            indices.len(),
+            names
+        ),
+        "/path/to/profiles.toml",
+        Some(&default_path.to_string_lossy()),
+    );
+    if let Some(button) = dialog.widget_for_response(gtk::ResponseType::Other(1)).and_downcast::<gtk::Button>() {
+        button.set_label("Export");
+    }
+
+    dialog.connect_response(move |d, response| {
+        if response != gtk::ResponseType::Other(1) {
+            d.close();
+            return;
+        }
+        let path = PathBuf::from(entry.text().trim());
+        let state = app_state.borrow();
+        match export_profiles(&state.config, &indices, &path) {
+            Ok(()) => {
+                FeedbackUtils::show_temporary_feedback(
+                    &state.main_settings_box,
+                    &format!("Exported {} profiles", indices.len()),
+                    2,
+                );
+                d.close();
+            }
+            Err(e) => show_profile_error(d, &e),
+        }
+    });
+
+    dialog.present();
+}
@@ -0,0 +1,19 @@
+//! Multipliers offered for lsfg-vk
+//! lsfg-vk does not report its release anywhere reliable (the layer
+//! manifest's `implementation_version` is the layer interface version), so
+//! the dropdown offers the multipliers every release accepts and anything
+//! higher goes through the "Custom…" entry.
+
+/// Multipliers in the dropdown: 1 (off) and those every lsfg-vk release accepts
+pub const MULTIPLIER_CHOICES: &[u32] = &[1, 2, 3, 4];
+
+/// Tooltip of the multiplier dropdown
+pub const MULTIPLIER_TOOLTIP: &str = "Higher multipliers depend on the installed lsfg-vk; pick Custom… to enter one";
+
+/// Dropdown label for a multiplier value
+pub fn multiplier_label(multiplier: u32) -> String {
+    match multiplier {
+        1 => "off".to_string(),
+        _ => multiplier.to_string(),
+    }
+}
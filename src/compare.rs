@@ -0,0 +1,263 @@
+//! Side-by-side comparison of profiles
+//! Lists every field of `profile_fields::PROFILE_FIELDS` for two or more
+//! profiles, highlights the ones that differ and can copy one profile's value
+//! to the others.
+
+use gtk::prelude::*;
+use gtk::{Box, Button, CheckButton, Grid, Label, Orientation, ScrolledWindow};
+use libadwaita::prelude::*;
+use libadwaita::ApplicationWindow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::app_state::AppState;
+use crate::capabilities::multiplier_label;
+use crate::config::GameProfile;
+use crate::default_profile::{is_default_profile, DEFAULT_PROFILE_LABEL};
+use crate::profile_fields::{FieldValue, ProfileField};
+use crate::profile_manager::show_profile_error;
+use crate::ui_components::{ButtonFactory, FeedbackUtils};
+
+/// One field across the compared profiles, in the order they were given
+pub struct FieldComparison {
+    pub field: ProfileField,
+    pub values: Vec<FieldValue>,
+}
+
+impl FieldComparison {
+    pub fn differs(&self) -> bool {
+        self.values.windows(2).any(|pair| pair[0] != pair[1])
+    }
+}
+
+pub fn compare_profiles(profiles: &[&GameProfile]) -> Vec<FieldComparison> {
+    ProfileField::all()
+        .map(|field| FieldComparison {
+            field,
+            values: profiles.iter().map(|profile| field.value(profile)).collect(),
+        })
+        .collect()
+}
+
+/// Text of a value in the comparison table
+fn display_value(field: ProfileField, value: &FieldValue) -> String {
+    match (field, value) {
+        (ProfileField::Multiplier, FieldValue::Choice(Some(text))) => {
+            text.parse().map(multiplier_label).unwrap_or_else(|_| text.clone())
+        }
+        (ProfileField::Gpu, FieldValue::Choice(None)) => "Automatic".to_string(),
+        _ => value.describe(),
+    }
+}
+
+struct CompareView {
+    app_state: Rc<RefCell<AppState>>,
+    window: ApplicationWindow,
+    // Profiles are tracked by name so edits elsewhere do not mix them up
+    exes: Vec<String>,
+    grid: Grid,
+    summary_label: Label,
+    only_differences_check: CheckButton,
+}
+
+impl CompareView {
+    // Rebuilds the table from the current config
+    fn refresh(self: &Rc<Self>) {
+        while let Some(child) = self.grid.first_child() {
+            self.grid.remove(&child);
+        }
+
+        let state = self.app_state.borrow();
+        let profiles: Vec<&GameProfile> = self.exes.iter()
+            .filter_map(|exe| state.config.game.iter().find(|profile| profile.exe == *exe))
+            .collect();
+        if profiles.len() < 2 {
+            self.summary_label.set_label("Fewer than two of the compared profiles still exist");
+            return;
+        }
+
+        let header = Label::builder()
+            .label("Setting")
+            .halign(gtk::Align::Start)
+            .css_classes(["heading"])
+            .build();
+        self.grid.attach(&header, 0, 0, 1, 1);
+        for (column, profile) in profiles.iter().enumerate() {
+            let name = Label::builder()
+                .label(if is_default_profile(profile) { DEFAULT_PROFILE_LABEL } else { &profile.exe })
+                .halign(gtk::Align::Start)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .tooltip_text(&profile.exe)
+                .css_classes(["heading"])
+                .build();
+            self.grid.attach(&name, column as i32 + 1, 0, 1, 1);
+        }
+
+        let comparisons = compare_profiles(&profiles);
+        let differing = comparisons.iter().filter(|comparison| comparison.differs()).count();
+        self.summary_label.set_label(&match differing {
+            0 => "All settings are the same".to_string(),
+            1 => "1 setting differs".to_string(),
+            _ => format!("{} settings differ", differing),
+        });
+
+        let only_differences = self.only_differences_check.is_active();
+        let mut row = 1;
+        for comparison in &comparisons {
+            let differs = comparison.differs();
+            if only_differences && !differs {
+                continue;
+            }
+            let descriptor = comparison.field.descriptor();
+            let title = Label::builder()
+                .label(descriptor.title)
+                .halign(gtk::Align::Start)
+                .tooltip_text(format!("[[game]] {}", descriptor.key))
+                .build();
+            self.grid.attach(&title, 0, row, 1, 1);
+
+            for (column, value) in comparison.values.iter().enumerate() {
+                let cell = Box::builder()
+                    .orientation(Orientation::Horizontal)
+                    .spacing(6)
+                    .css_classes(["compare-cell"])
+                    .build();
+                let value_label = Label::builder()
+                    .label(display_value(comparison.field, value))
+                    .halign(gtk::Align::Start)
+                    .hexpand(true)
+                    .build();
+                cell.append(&value_label);
+                if differs {
+                    cell.add_css_class("compare-diff");
+                    let copy_button = ButtonFactory::create_flat_icon_button(
+                        "edit-copy-symbolic",
+                        "Copy this value to the other profiles",
+                    );
+                    let view = self.clone();
+                    let source = profiles[column].exe.clone();
+                    let field = comparison.field;
+                    let value = value.clone();
+                    copy_button.connect_clicked(move |_| view.copy_value_across(&source, field, &value));
+                    cell.append(&copy_button);
+                }
+                self.grid.attach(&cell, column as i32 + 1, row, 1, 1);
+            }
+            row += 1;
+        }
+    }
+
+    // Sets a value on every compared profile other than `source`
+    fn copy_value_across(self: &Rc<Self>, source: &str, field: ProfileField, value: &FieldValue) {
+        let mut state = self.app_state.borrow_mut();
+        let targets: Vec<usize> = self.exes.iter()
+            .filter(|exe| exe.as_str() != source)
+            .filter_map(|exe| state.config.game.iter().position(|profile| profile.exe == *exe))
+            .collect();
+        match state.apply_bulk_edit(&targets, &[(field, value.clone())]) {
+            Ok(changed) => {
+                state.populate_sidebar_with_handlers(Some(self.app_state.clone()));
+                let main_settings_box = state.main_settings_box.clone();
+                drop(state);
+                self.app_state.borrow().update_main_window_from_profile();
+                FeedbackUtils::show_temporary_feedback(
+                    &main_settings_box,
+                    &format!("Copied {} to {} profiles", field.descriptor().title, changed),
+                    2,
+                );
+            }
+            Err(e) => {
+                drop(state);
+                show_profile_error(&self.window, &e);
+            }
+        }
+        self.refresh();
+    }
+}
+
+/// Opens a window comparing the profiles selected in the sidebar
+pub fn show_compare_window(app_state: Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let exes: Vec<String> = state.selected_profile_indices().iter()
+        .filter_map(|index| state.config.game.get(*index))
+        .map(|profile| profile.exe.clone())
+        .collect();
+    drop(state);
+    if exes.len() < 2 {
+        show_profile_error(&main_window, "Select at least two profiles to compare");
+        return;
+    }
+
+    let window = ApplicationWindow::builder()
+        .title(format!("Compare {} Profiles", exes.len()))
+        .transient_for(&main_window)
+        .default_width(720)
+        .default_height(420)
+        .build();
+
+    let grid = Grid::builder()
+        .row_spacing(6)
+        .column_spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    let scrolled_window = ScrolledWindow::builder()
+        .hexpand(true)
+        .vexpand(true)
+        .child(&grid)
+        .build();
+
+    let summary_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .css_classes(["dim-label"])
+        .build();
+    let only_differences_check = CheckButton::with_label("Only show differences");
+    let close_button = Button::with_label("Close");
+
+    let footer = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    footer.append(&summary_label);
+    footer.append(&only_differences_check);
+    footer.append(&close_button);
+
+    let content = Box::builder().orientation(Orientation::Vertical).build();
+    content.append(&scrolled_window);
+    content.append(&footer);
+    window.set_content(Some(&content));
+
+    let view = Rc::new(CompareView {
+        app_state,
+        window: window.clone(),
+        exes,
+        grid,
+        summary_label,
+        only_differences_check: only_differences_check.clone(),
+    });
+    view.refresh();
+
+    let view_clone = view.clone();
+    only_differences_check.connect_toggled(move |_| view_clone.refresh());
+
+    // Pick up edits made in the main window while this one was in the background
+    let view_clone = view.clone();
+    window.connect_is_active_notify(move |window| {
+        if window.is_active() {
+            view_clone.refresh();
+        }
+    });
+
+    let window_clone = window.clone();
+    close_button.connect_clicked(move |_| window_clone.close());
+
+    window.present();
+}
@@ -24,10 +24,17 @@ pub struct OrderedGlobalConfig {
     pub global: Option<GlobalConfig>,
 }
 
+// Every key is optional so lsfg-vk's own default applies when it is left out.
+// The settings page is generated from `global_options::GLOBAL_OPTIONS`.
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct GlobalConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")] // Only serialize if Some
     pub dll: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_fp16: Option<bool>,
+    /// Keys this version does not know, written back unchanged
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -40,6 +47,9 @@ pub struct GameProfile {
     pub performance_mode: bool,
     pub hdr_mode: bool,
     pub experimental_present_mode: String,
+    /// GPU used for frame generation as a PCI bus ID or `vendor:device`; lsfg-vk picks one if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu: Option<String>,
 }
 
 // Default values for a new game profile
@@ -52,6 +62,7 @@ impl Default for GameProfile {
             performance_mode: true,
             hdr_mode: false,
             experimental_present_mode: "vsync".to_string(),
+            gpu: None,
         }
     }
 }
@@ -79,14 +90,18 @@ where
 
 // --- Configuration File Handling Functions ---
 
-pub fn get_config_path() -> Result<PathBuf, io::Error> {
+pub fn get_config_dir() -> Result<PathBuf, io::Error> {
     let config_dir = dirs::config_dir()
         .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Could not find config directory"))?
         .join("lsfg-vk");
     
     fs::create_dir_all(&config_dir)?; // Ensure directory exists
     println!("Config directory: {:?}", config_dir);
-    Ok(config_dir.join("conf.toml"))
+    Ok(config_dir)
+}
+
+pub fn get_config_path() -> Result<PathBuf, io::Error> {
+    Ok(get_config_dir()?.join("conf.toml"))
 }
 
 
@@ -135,7 +150,41 @@ pub fn save_config(config: &Config) -> Result<(), io::Error> {
     println!("Attempting to save config to: {:?}", config_path);
     let toml_string = toml::to_string_pretty(config)
         .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to serialize TOML: {}", e)))?;
-    fs::write(&config_path, toml_string)?;
+    fs::write(&config_path, &toml_string)?;
     println!("Successfully saved config.");
+
+    // Keep a browsable history of saves next to the config
+    if let Err(e) = crate::history::record_snapshot(config, &toml_string) {
+        eprintln!("Failed to record config history: {}", e);
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_global_keys_survive_a_save() {
+        let input = r#"version = 1
+
+[global]
+dll = "/path/to/Lossless.dll"
+future_option = 3
+
+[global.future_table]
+enabled = true
+
+[[game]]
+exe = "game.exe"
+"#;
+        let config: Config = toml::from_str(input).unwrap();
+        let global = config.ordered_global.global.as_ref().unwrap();
+        assert_eq!(global.dll.as_deref(), Some("/path/to/Lossless.dll"));
+        assert_eq!(global.extra.get("future_option").and_then(toml::Value::as_integer), Some(3));
+
+        let saved: toml::Table = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        let expected: toml::Table = toml::from_str(input).unwrap();
+        assert_eq!(saved["global"], expected["global"]);
+    }
+}
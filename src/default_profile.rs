@@ -0,0 +1,43 @@
+//! Default (fallback) profile support
+//! lsfg-vk only picks a profile by executable name and has no wildcard entry.
+//! The default profile is therefore stored as a regular `[[game]]` entry with a
+//! reserved name, and games are pointed at it through `LSFG_PROCESS`, which lsfg-vk
+//! checks before falling back to the executable name.
+
+use crate::config::{Config, GameProfile};
+
+/// Reserved `exe` value of the default profile in conf.toml
+pub const DEFAULT_PROFILE_EXE: &str = "lsfg-default";
+
+/// Label shown for the default profile in the sidebar
+pub const DEFAULT_PROFILE_LABEL: &str = "Default (all other games)";
+
+/// Explains precedence between the default profile and specific profiles
+pub const DEFAULT_PROFILE_DESCRIPTION: &str = "lsfg-vk has no wildcard matching, so this profile is saved as a regular \
+[[game]] entry named \"lsfg-default\". A game uses it when it is started with LSFG_PROCESS=lsfg-default, which the Launch button, \
+Steam launch options and desktop launchers of this profile set for you. \
+Games without that variable are matched by executable name against your other profiles.";
+
+pub fn is_default_profile(profile: &GameProfile) -> bool {
+    profile.exe == DEFAULT_PROFILE_EXE
+}
+
+/// Moves an existing default profile to index 0 so it is pinned in the sidebar
+pub fn pin_default_profile(config: &mut Config) {
+    if let Some(index) = config.game.iter().position(is_default_profile).filter(|index| *index != 0) {
+        let profile = config.game.remove(index);
+        config.game.insert(0, profile);
+    }
+}
+
+/// Adds the default profile at index 0 if there is none yet; returns whether it was added
+pub fn add_default_profile(config: &mut Config) -> bool {
+    if config.game.iter().any(is_default_profile) {
+        return false;
+    }
+    config.game.insert(0, GameProfile {
+        exe: DEFAULT_PROFILE_EXE.to_string(),
+        ..Default::default()
+    });
+    true
+}
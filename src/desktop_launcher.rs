@@ -0,0 +1,250 @@
+//! Desktop launchers for non-Steam games
+//! A launcher is a wrapper script that exports the lsfg-vk environment of a
+//! profile and execs the game, optionally paired with a `.desktop` entry so it
+//! shows up in the applications menu. Generated files are tracked in the
+//! profile metadata and rewritten whenever the profile changes.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::gamescope::gamescope_prefix;
+use crate::launcher::{build_launch_environment, prefixed_command, shell_quote, LaunchSettings};
+use crate::profile_meta::ProfileMetaFile;
+
+/// Files generated for a profile, stored in the profile metadata file
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LauncherFiles {
+    pub wrapper_script: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desktop_entry: Option<String>,
+}
+
+impl LauncherFiles {
+    pub fn paths(&self) -> Vec<&str> {
+        std::iter::once(self.wrapper_script.as_str())
+            .chain(self.desktop_entry.as_deref())
+            .collect()
+    }
+}
+
+/// File name friendly form of a profile name ("Cyberpunk 2077.exe" → "cyberpunk-2077-exe")
+pub fn launcher_slug(exe: &str) -> String {
+    let slug: String = exe.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() { "profile".to_string() } else { slug }
+}
+
+/// Name shared by a profile's launcher files. Different names can give the same
+/// slug ("Game.exe" and "game-exe"), so a hash of the exact name is appended.
+pub fn launcher_file_stem(exe: &str) -> String {
+    format!("{}-{:08x}", launcher_slug(exe), name_hash(exe))
+}
+
+// 32-bit FNV-1a; unlike std's hasher it gives the same value in every build,
+// so file names stay put across updates
+fn name_hash(text: &str) -> u32 {
+    text.bytes().fold(0x811c9dc5, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193))
+}
+
+pub fn wrapper_script_path(exe: &str) -> Result<PathBuf, String> {
+    let data_dir = dirs::data_dir().ok_or("Could not determine the data directory")?;
+    Ok(data_dir.join("lsfg-vk-ui").join("launchers").join(format!("{}.sh", launcher_file_stem(exe))))
+}
+
+pub fn desktop_entry_path(exe: &str) -> Result<PathBuf, String> {
+    let data_dir = dirs::data_dir().ok_or("Could not determine the data directory")?;
+    Ok(data_dir.join("applications").join(format!("lsfg-vk-ui-{}.desktop", launcher_file_stem(exe))))
+}
+
+/// Shell script that exports the lsfg-vk environment and execs the game
+pub fn generate_wrapper_script(exe: &str, settings: &LaunchSettings, prefix: &[String]) -> String {
+    let mut script = String::from("#!/bin/sh\n");
+    script.push_str(&format!(
+        "# Generated by lsfg-vk-ui for profile '{}'. Changes are overwritten when the profile is saved.\n",
+        without_control_chars(exe)
+    ));
+    for (key, value) in build_launch_environment(exe, settings) {
+        script.push_str(&format!("export {}={}\n", key, shell_quote(&value)));
+    }
+    if let Some(dir) = settings.working_dir.as_deref().filter(|dir| !dir.trim().is_empty()) {
+        script.push_str(&format!("cd {} || exit 1\n", shell_quote(dir)));
+    }
+    script.push_str(&format!("exec {} \"$@\"\n", prefixed_command(prefix, &settings.command)));
+    script
+}
+
+// A name from a hand-edited conf.toml may hold a newline, which would start a
+// new line of the script
+fn without_control_chars(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { '?' } else { c }).collect()
+}
+
+/// Escapes a desktop entry string value, which has to stay on one line
+fn desktop_string_escape(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '\\' => vec!['\\', '\\'],
+            '\n' => vec!['\\', 'n'],
+            '\t' => vec!['\\', 't'],
+            '\r' => vec!['\\', 'r'],
+            c if c.is_control() => vec![],
+            c => vec![c],
+        })
+        .collect()
+}
+
+/// Quotes an argument for the Exec key of a desktop entry
+fn desktop_exec_quote(argument: &str) -> String {
+    let escaped: String = argument.chars()
+        .flat_map(|c| match c {
+            '"' | '`' | '$' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect();
+    // The Exec value is itself a string value, so backslashes are escaped once more
+    format!("\"{}\"", escaped.replace('\\', "\\\\"))
+}
+
+/// `.desktop` entry that starts the game through its wrapper script
+pub fn generate_desktop_entry(exe: &str, wrapper_script: &Path) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name={exe} (lsfg-vk)\n\
+         Comment=Start {exe} with the lsfg-vk profile '{exe}'\n\
+         Exec={exec}\n\
+         Icon=applications-games\n\
+         Terminal=false\n\
+         Categories=Game;\n\
+         X-Lsfg-Vk-Ui-Profile={exe}\n",
+        exe = desktop_string_escape(exe),
+        exec = desktop_exec_quote(&wrapper_script.to_string_lossy()),
+    )
+}
+
+/// Writes a file only if its contents differ, creating parent directories as needed
+fn write_if_changed(path: &Path, contents: &str) -> Result<(), String> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Writes the wrapper script (and the desktop entry if requested) for a profile
+pub fn create_launcher(
+    exe: &str,
+    settings: &LaunchSettings,
+    prefix: &[String],
+    with_desktop_entry: bool,
+) -> Result<LauncherFiles, String> {
+    if !settings.is_configured() {
+        return Err("Configure a launch command for this profile first".to_string());
+    }
+
+    let wrapper = wrapper_script_path(exe)?;
+    write_if_changed(&wrapper, &generate_wrapper_script(exe, settings, prefix))?;
+    fs::set_permissions(&wrapper, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Failed to make {} executable: {}", wrapper.display(), e))?;
+
+    let desktop_entry = if with_desktop_entry {
+        let path = desktop_entry_path(exe)?;
+        write_if_changed(&path, &generate_desktop_entry(exe, &wrapper))?;
+        Some(path.to_string_lossy().into_owned())
+    } else {
+        None
+    };
+
+    Ok(LauncherFiles {
+        wrapper_script: wrapper.to_string_lossy().into_owned(),
+        desktop_entry,
+    })
+}
+
+/// Deletes generated files; files that are already gone are not an error
+pub fn remove_launcher(files: &LauncherFiles) {
+    for path in files.paths() {
+        remove_launcher_file(path);
+    }
+}
+
+pub fn remove_launcher_file(path: &str) {
+    match fs::remove_file(path) {
+        Ok(()) => println!("Removed launcher file {}", path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => eprintln!("Failed to remove launcher file {}: {}", path, e),
+    }
+}
+
+/// Brings every tracked launcher in line with its profile: renamed profiles get
+/// new file names, changed launch settings are written out, and launchers of
+/// profiles without a launch command are removed.
+pub fn sync_launchers(config: &Config, meta: &mut ProfileMetaFile) {
+    for (exe, entry) in meta.profile.iter_mut() {
+        let Some(files) = entry.launcher.clone() else {
+            continue;
+        };
+        let Some(settings) = entry.launch.as_ref().filter(|settings| settings.is_configured()) else {
+            remove_launcher(&files);
+            entry.launcher = None;
+            continue;
+        };
+
+        let prefix = config.game.iter()
+            .find(|profile| &profile.exe == exe)
+            .map(|profile| gamescope_prefix(Some(entry), profile))
+            .unwrap_or_default();
+        match create_launcher(exe, settings, &prefix, files.desktop_entry.is_some()) {
+            Ok(updated) => {
+                // Files left behind under an old profile name
+                for path in files.paths() {
+                    if !updated.paths().contains(&path) {
+                        remove_launcher_file(path);
+                    }
+                }
+                entry.launcher = Some(updated);
+            }
+            Err(e) => eprintln!("Failed to update launcher for '{}': {}", exe, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_cannot_add_lines_to_generated_files() {
+        let exe = "game\nrm -rf ~\r\\x.exe";
+        let settings = LaunchSettings { command: "true".to_string(), ..LaunchSettings::default() };
+
+        let script = generate_wrapper_script(exe, &settings, &[]);
+        assert!(script.lines().nth(1).unwrap().contains("profile 'game?rm -rf ~?\\x.exe'. Changes"));
+        // The value itself reaches the game unchanged, inside quotes
+        assert!(script.contains("export LSFG_PROCESS='game\nrm -rf ~\r\\x.exe'\n"));
+
+        let entry = generate_desktop_entry(exe, Path::new("/tmp/launcher.sh"));
+        assert_eq!(entry.lines().count(), 9);
+        assert!(entry.contains("Name=game\\nrm -rf ~\\r\\\\x.exe (lsfg-vk)\n"));
+    }
+
+    #[test]
+    fn launcher_file_stems_differ_for_names_with_the_same_slug() {
+        assert_eq!(launcher_slug("Game.exe"), launcher_slug("game-exe"));
+        assert_ne!(launcher_file_stem("Game.exe"), launcher_file_stem("game-exe"));
+    }
+
+    #[test]
+    fn launcher_file_stem_is_stable() {
+        assert_eq!(launcher_file_stem("Cyberpunk 2077.exe"), format!("cyberpunk-2077-exe-{:08x}", name_hash("Cyberpunk 2077.exe")));
+        assert_eq!(name_hash(""), 0x811c9dc5);
+        assert_eq!(name_hash("a"), 0xe40c292c);
+    }
+}
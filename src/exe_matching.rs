@@ -0,0 +1,331 @@
+//! Executable matching
+//! Mirrors how lsfg-vk picks a profile for a process and adds UI-side glob
+//! patterns. lsfg-vk itself only compares literal executable names, so
+//! patterns are expanded into concrete `[[game]]` entries before they take effect.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, GameProfile};
+use crate::default_profile::is_default_profile;
+use crate::profile_meta::ProfileMetaFile;
+
+/// Why a profile was picked for a process
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchReason {
+    /// The profile's `exe` equals the executable name; this is what lsfg-vk does
+    ExactName,
+    /// One of the profile's UI-side patterns matches; lsfg-vk needs an expanded entry
+    Pattern(String),
+    /// Nothing specific matched; only applies when started with LSFG_PROCESS=lsfg-default
+    DefaultProfile,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchResult {
+    pub profile_index: usize,
+    pub reason: MatchReason,
+}
+
+impl MatchResult {
+    /// Explains the result in a sentence suitable for the tester box
+    pub fn explain(&self, config: &Config) -> String {
+        let name = &config.game[self.profile_index].exe;
+        match &self.reason {
+            MatchReason::ExactName => format!(
+                "'{}' wins: its name matches the executable exactly. lsfg-vk uses the first exact match in conf.toml.",
+                name
+            ),
+            MatchReason::Pattern(pattern) => format!(
+                "'{}' wins through pattern '{}'. lsfg-vk only sees it after the pattern has been expanded into a concrete entry.",
+                name, pattern
+            ),
+            MatchReason::DefaultProfile => "No profile matches by name. The default profile applies only if the game \
+                is started with LSFG_PROCESS=lsfg-default; otherwise lsfg-vk stays off.".to_string(),
+        }
+    }
+}
+
+/// Matches `text` against a glob pattern supporting `*` and `?`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Full-path patterns contain a directory separator and are matched against the whole path
+pub fn is_path_pattern(pattern: &str) -> bool {
+    pattern.contains('/')
+}
+
+/// Returns the executable name of a process given either a bare name or a full path
+pub fn executable_name(process: &str) -> &str {
+    process.rsplit('/').next().unwrap_or(process)
+}
+
+// In a path pattern each wildcard stays within one directory level, like a shell glob
+fn pattern_matches(pattern: &str, process: &str) -> bool {
+    if is_path_pattern(pattern) {
+        let (pattern_parts, path_parts): (Vec<&str>, Vec<&str>) = (pattern.split('/').collect(), process.split('/').collect());
+        pattern_parts.len() == path_parts.len()
+            && pattern_parts.iter().zip(&path_parts).all(|(pattern, part)| glob_match(pattern, part))
+    } else {
+        glob_match(pattern, executable_name(process))
+    }
+}
+
+/// Splits the patterns entry text ("a*.exe; /games/*/b.exe") into individual patterns
+pub fn parse_patterns(text: &str) -> Vec<String> {
+    text.split(';')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Finds the profile that would apply to a process, following lsfg-vk's precedence:
+/// exact names first (in file order), then UI patterns, then the default profile
+pub fn find_matching_profile(config: &Config, meta: &ProfileMetaFile, process: &str) -> Option<MatchResult> {
+    let name = executable_name(process.trim());
+    if name.is_empty() {
+        return None;
+    }
+
+    let real_profiles = || config.game.iter().enumerate().filter(|(_, p)| !is_default_profile(p));
+
+    if let Some((index, _)) = real_profiles().find(|(_, p)| p.exe == name) {
+        return Some(MatchResult { profile_index: index, reason: MatchReason::ExactName });
+    }
+
+    for (index, profile) in real_profiles() {
+        let patterns = meta.get(&profile.exe).map(|m| m.patterns.as_slice()).unwrap_or_default();
+        if let Some(pattern) = patterns.iter().find(|pattern| pattern_matches(pattern, process.trim())) {
+            return Some(MatchResult { profile_index: index, reason: MatchReason::Pattern(pattern.clone()) });
+        }
+    }
+
+    config.game.iter().position(is_default_profile)
+        .map(|index| MatchResult { profile_index: index, reason: MatchReason::DefaultProfile })
+}
+
+/// Lists executables a path pattern can currently expand to by walking the
+/// directories it names. Wildcards may appear in any component; each one stays
+/// within a single directory level.
+fn scan_path_pattern(pattern: &str) -> Vec<String> {
+    let Some(relative) = pattern.strip_prefix('/') else {
+        return Vec::new();
+    };
+    let components: Vec<&str> = relative.split('/').filter(|component| !component.is_empty()).collect();
+    let Some((file_pattern, dir_patterns)) = components.split_last() else {
+        return Vec::new();
+    };
+
+    let mut dirs = vec![PathBuf::from("/")];
+    for component in dir_patterns {
+        dirs = dirs.iter()
+            .flat_map(|dir| matching_entries(dir, component))
+            .filter(|path| path.is_dir())
+            .collect();
+    }
+    let mut names: Vec<String> = dirs.iter()
+        .flat_map(|dir| matching_entries(dir, file_pattern))
+        .filter(|path| path.is_file())
+        .filter_map(|path| path.file_name().and_then(|name| name.to_str()).map(str::to_string))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// Entries of `dir` named by one pattern component; literal names are not listed
+fn matching_entries(dir: &Path, component: &str) -> Vec<PathBuf> {
+    if !component.contains(['*', '?']) {
+        return vec![dir.join(component)];
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_str().is_some_and(|name| glob_match(component, name)))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Adds a concrete `[[game]]` entry for every candidate executable that one of the
+/// profile's patterns matches. Generated entries inherit everything from the
+/// pattern's profile so they stay in sync. Returns the names that were added.
+pub fn expand_patterns(
+    config: &mut Config,
+    meta: &mut ProfileMetaFile,
+    profile_index: usize,
+    candidates: &[String],
+) -> Vec<String> {
+    let Some(owner) = config.game.get(profile_index).cloned() else {
+        return Vec::new();
+    };
+    let patterns = meta.get(&owner.exe).map(|m| m.patterns.clone()).unwrap_or_default();
+
+    let mut names: Vec<String> = Vec::new();
+    for pattern in &patterns {
+        let mut matches: Vec<String> = candidates.iter()
+            .filter(|candidate| pattern_matches(pattern, candidate))
+            .map(|candidate| executable_name(candidate).to_string())
+            .collect();
+        if is_path_pattern(pattern) {
+            matches.extend(scan_path_pattern(pattern));
+        }
+        for name in matches {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    let mut added = Vec::new();
+    for name in names {
+        if name == owner.exe || config.game.iter().any(|p| p.exe == name) {
+            continue;
+        }
+        config.game.push(GameProfile { exe: name.clone(), ..owner.clone() });
+        let entry = meta.entry(&name);
+        entry.inherits = Some(owner.exe.clone());
+        entry.expanded_from = Some(owner.exe.clone());
+        added.push(name);
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::default_profile::DEFAULT_PROFILE_EXE;
+
+    fn config(names: &[&str]) -> Config {
+        Config {
+            game: names.iter().map(|exe| GameProfile { exe: exe.to_string(), ..GameProfile::default() }).collect(),
+            ..Config::default()
+        }
+    }
+
+    fn with_patterns(entries: &[(&str, &[&str])]) -> ProfileMetaFile {
+        let mut meta = ProfileMetaFile::default();
+        for (exe, patterns) in entries {
+            meta.entry(exe).patterns = patterns.iter().map(|pattern| pattern.to_string()).collect();
+        }
+        meta
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("game.exe", "game.exe"));
+        assert!(!glob_match("game.exe", "game.exe2"));
+        assert!(glob_match("*.exe", "game.exe"));
+        assert!(glob_match("g?me*", "game-x64"));
+        assert!(!glob_match("g?me", "gme"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("**", "anything"));
+        // The star has to give characters back to let the rest match
+        assert!(glob_match("*a*b", "xaxaxb"));
+        assert!(glob_match("*.exe.exe", "a.exe.exe.exe"));
+        assert!(!glob_match("*a*b", "xaxaxc"));
+    }
+
+    #[test]
+    fn parses_pattern_lists() {
+        assert_eq!(parse_patterns(" a*.exe; /games/*/b.exe ;; "), ["a*.exe", "/games/*/b.exe"]);
+        assert!(parse_patterns("  ").is_empty());
+    }
+
+    #[test]
+    fn exact_names_beat_patterns_and_the_default() {
+        let config = config(&[DEFAULT_PROFILE_EXE, "wide", "Game.exe"]);
+        let meta = with_patterns(&[("wide", &["*.exe"])]);
+
+        let result = find_matching_profile(&config, &meta, "/opt/games/Game.exe").unwrap();
+        assert_eq!((result.profile_index, result.reason), (2, MatchReason::ExactName));
+
+        let result = find_matching_profile(&config, &meta, "Other.exe").unwrap();
+        assert_eq!((result.profile_index, result.reason), (1, MatchReason::Pattern("*.exe".to_string())));
+
+        let result = find_matching_profile(&config, &meta, "native-game").unwrap();
+        assert_eq!((result.profile_index, result.reason), (0, MatchReason::DefaultProfile));
+    }
+
+    #[test]
+    fn patterns_are_tried_in_file_order() {
+        let config = config(&["first", "second"]);
+        let meta = with_patterns(&[("first", &["/games/*"]), ("second", &["*.exe"])]);
+
+        // Name patterns see only the executable name, path patterns the whole path
+        assert_eq!(find_matching_profile(&config, &meta, "/games/a.exe").unwrap().profile_index, 0);
+        assert_eq!(find_matching_profile(&config, &meta, "/other/a.exe").unwrap().profile_index, 1);
+        assert!(find_matching_profile(&config, &meta, "/other/a").is_none());
+        // A wildcard in a path pattern does not reach into subdirectories
+        assert_eq!(find_matching_profile(&config, &meta, "/games/sub/a").map(|result| result.profile_index), None);
+        assert!(find_matching_profile(&config, &meta, "  ").is_none());
+    }
+
+    #[test]
+    fn the_default_profile_is_never_an_exact_match() {
+        let config = config(&[DEFAULT_PROFILE_EXE]);
+        let result = find_matching_profile(&config, &ProfileMetaFile::default(), DEFAULT_PROFILE_EXE).unwrap();
+        assert_eq!(result.reason, MatchReason::DefaultProfile);
+    }
+
+    #[test]
+    fn expands_patterns_into_inheriting_entries() {
+        let mut config = config(&["shooters", "known.exe"]);
+        config.game[0].multiplier = 3;
+        let mut meta = with_patterns(&[("shooters", &["*.exe", "/games/*"])]);
+        let candidates = ["known.exe", "/games/doom.exe", "quake.exe", "quake.exe", "native"].map(str::to_string);
+
+        let added = expand_patterns(&mut config, &mut meta, 0, &candidates);
+
+        assert_eq!(added, ["doom.exe", "quake.exe"]);
+        assert_eq!(config.game.len(), 4);
+        let doom = config.game.iter().find(|profile| profile.exe == "doom.exe").unwrap();
+        assert_eq!(doom.multiplier, 3);
+        assert_eq!(meta.inherits("doom.exe"), Some("shooters"));
+        assert_eq!(meta.get("quake.exe").unwrap().expanded_from.as_deref(), Some("shooters"));
+
+        // Running it again adds nothing
+        assert!(expand_patterns(&mut config, &mut meta, 0, &candidates).is_empty());
+    }
+
+    #[test]
+    fn path_patterns_scan_wildcard_directories() {
+        let root = tempfile::tempdir().unwrap();
+        for (dir, file) in [("Doom", "doom.exe"), ("Quake", "quake.exe"), ("Quake", "readme.txt"), ("Other/Deep", "deep.exe")] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+            fs::write(root.path().join(dir).join(file), "").unwrap();
+        }
+        fs::create_dir_all(root.path().join("Folder.exe")).unwrap();
+        let root_text = root.path().to_str().unwrap();
+
+        assert_eq!(scan_path_pattern(&format!("{}/*/*.exe", root_text)), ["doom.exe", "quake.exe"]);
+        assert_eq!(scan_path_pattern(&format!("{}/Q*/*", root_text)), ["quake.exe", "readme.txt"]);
+        assert_eq!(scan_path_pattern(&format!("{}/*/Deep/*.exe", root_text)), ["deep.exe"]);
+        assert!(scan_path_pattern(&format!("{}/*.exe", root_text)).is_empty());
+        assert!(scan_path_pattern("relative/*.exe").is_empty());
+    }
+}
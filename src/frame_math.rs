@@ -0,0 +1,64 @@
+//! Frame-rate math for choosing a multiplier
+//! Frame generation multiplies the base frame rate and holds back one real
+//! frame to interpolate towards, which costs roughly one base frame time of latency.
+
+use gtk::prelude::*;
+
+/// Output FPS within this fraction of the refresh rate counts as matching it
+const REFRESH_TOLERANCE: f64 = 0.05;
+
+/// Multipliers the calculator compares
+pub const CALCULATOR_MULTIPLIERS: [u32; 3] = [2, 3, 4];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameRateEstimate {
+    pub multiplier: u32,
+    pub output_fps: f64,
+    pub exceeds_refresh: bool,
+    /// Extra latency from the held-back frame, in milliseconds
+    pub added_latency_ms: f64,
+}
+
+/// Estimates output FPS and latency for each multiplier
+pub fn estimate_frame_rates(base_fps: f64, refresh_rate: f64, multipliers: &[u32]) -> Vec<FrameRateEstimate> {
+    multipliers.iter().map(|&multiplier| {
+        let output_fps = base_fps * multiplier as f64;
+        FrameRateEstimate {
+            multiplier,
+            output_fps,
+            exceeds_refresh: output_fps > refresh_rate * (1.0 + REFRESH_TOLERANCE),
+            added_latency_ms: if base_fps > 0.0 { 1000.0 / base_fps } else { 0.0 },
+        }
+    }).collect()
+}
+
+/// The highest multiplier whose output still fits the refresh rate
+pub fn recommended_multiplier(estimates: &[FrameRateEstimate]) -> Option<u32> {
+    estimates.iter()
+        .filter(|estimate| !estimate.exceeds_refresh)
+        .map(|estimate| estimate.multiplier)
+        .max()
+}
+
+/// Suggests an `experimental_present_mode` for the given output and refresh rate
+pub fn suggest_present_mode(output_fps: f64, refresh_rate: f64) -> (&'static str, &'static str) {
+    if output_fps > refresh_rate * (1.0 + REFRESH_TOLERANCE) {
+        ("mailbox", "Output exceeds the refresh rate; mailbox drops surplus frames instead of queueing them up as latency.")
+    } else if output_fps >= refresh_rate * (1.0 - REFRESH_TOLERANCE) {
+        ("vsync", "Output matches the refresh rate; vsync paces every generated frame evenly without tearing.")
+    } else {
+        ("immediate", "Output stays below the refresh rate; immediate presents frames as soon as they are ready (best with VRR).")
+    }
+}
+
+/// Refresh rate in Hz of the monitor showing the window, or of the first monitor
+/// before the window is mapped, as reported by GDK
+pub fn detect_refresh_rate<W: IsA<gtk::Native>>(window: &W) -> Option<f64> {
+    let display = gtk::gdk::Display::default()?;
+    let monitor = window.as_ref().is_realized()
+        .then(|| display.monitor_at_surface(&window.surface()))
+        .flatten()
+        .or_else(|| display.monitors().item(0).and_downcast::<gtk::gdk::Monitor>())?;
+    let millihertz = monitor.refresh_rate();
+    (millihertz > 0).then(|| millihertz as f64 / 1000.0)
+}
@@ -0,0 +1,528 @@
+//! Gamepad input
+//! Joystick devices are read straight from `/dev/input` (evdev), so
+//! controllers work without Steam Input. Their raw events are turned into a
+//! few navigation inputs. A mock device, fed text commands through a FIFO,
+//! stands in for a controller when testing.
+
+use std::ffi::CString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+
+// Event types and codes from linux/input-event-codes.h
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const BTN_SOUTH: u16 = 0x130;
+const BTN_EAST: u16 = 0x131;
+const BTN_TL: u16 = 0x136;
+const BTN_TR: u16 = 0x137;
+const BTN_DPAD_UP: u16 = 0x220;
+const BTN_DPAD_DOWN: u16 = 0x221;
+const BTN_DPAD_LEFT: u16 = 0x222;
+const BTN_DPAD_RIGHT: u16 = 0x223;
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const ABS_HAT0X: u16 = 0x10;
+const ABS_HAT0Y: u16 = 0x11;
+
+/// Size of `struct input_event`: a timeval of two longs, then type, code and value
+pub const INPUT_EVENT_SIZE: usize = 2 * std::mem::size_of::<usize>() + 8;
+
+/// Stick deflection that counts as a direction, and the one it must fall below to let go
+const STICK_PRESS: f32 = 0.6;
+const STICK_RELEASE: f32 = 0.3;
+
+/// Environment variable naming a FIFO that takes mock controller commands,
+/// e.g. `echo down > $LSFG_VK_UI_MOCK_GAMEPAD`
+pub const MOCK_GAMEPAD_ENV: &str = "LSFG_VK_UI_MOCK_GAMEPAD";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    pub kind: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+pub fn parse_input_events(bytes: &[u8]) -> Vec<InputEvent> {
+    let offset = INPUT_EVENT_SIZE - 8;
+    bytes.chunks_exact(INPUT_EVENT_SIZE)
+        .map(|event| InputEvent {
+            kind: u16::from_ne_bytes([event[offset], event[offset + 1]]),
+            code: u16::from_ne_bytes([event[offset + 2], event[offset + 3]]),
+            value: i32::from_ne_bytes([event[offset + 4], event[offset + 5], event[offset + 6], event[offset + 7]]),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// A, the bottom face button
+    Activate,
+    /// B, the right face button
+    Back,
+    /// Left shoulder
+    PreviousProfile,
+    /// Right shoulder
+    NextProfile,
+}
+
+impl GamepadInput {
+    pub fn is_direction(self) -> bool {
+        matches!(self, GamepadInput::Up | GamepadInput::Down | GamepadInput::Left | GamepadInput::Right)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadEvent {
+    Pressed(GamepadInput),
+    Released(GamepadInput),
+}
+
+// Axes that can hold a direction, as indices into `GamepadTranslator::held`;
+// the stick axes minus `STICK_X` index `stick_ranges`
+const HAT_X: usize = 0;
+const HAT_Y: usize = 1;
+const STICK_X: usize = 2;
+const STICK_Y: usize = 3;
+
+/// Turns raw events of one device into navigation inputs
+#[derive(Debug, Default)]
+pub struct GamepadTranslator {
+    // The direction each axis currently holds
+    held: [Option<GamepadInput>; 4],
+    // Minimum and maximum of each stick axis
+    stick_ranges: [Option<(i32, i32)>; 2],
+}
+
+impl GamepadTranslator {
+    /// Sets the range a stick axis (`ABS_X` or `ABS_Y`) reports. Without one,
+    /// 0..=255 is assumed until a value outside it shows a signed 16-bit range.
+    pub fn set_stick_range(&mut self, code: u16, minimum: i32, maximum: i32) {
+        if let Some(range) = stick_index(code).map(|index| &mut self.stick_ranges[index]) {
+            *range = (maximum > minimum).then_some((minimum, maximum));
+        }
+    }
+
+    pub fn translate(&mut self, event: InputEvent) -> Vec<GamepadEvent> {
+        match event.kind {
+            EV_KEY => {
+                let input = match event.code {
+                    BTN_SOUTH => GamepadInput::Activate,
+                    BTN_EAST => GamepadInput::Back,
+                    BTN_TL => GamepadInput::PreviousProfile,
+                    BTN_TR => GamepadInput::NextProfile,
+                    BTN_DPAD_UP => GamepadInput::Up,
+                    BTN_DPAD_DOWN => GamepadInput::Down,
+                    BTN_DPAD_LEFT => GamepadInput::Left,
+                    BTN_DPAD_RIGHT => GamepadInput::Right,
+                    _ => return Vec::new(),
+                };
+                // 2 is the kernel's key repeat, which navigation repeats itself
+                match event.value {
+                    0 => vec![GamepadEvent::Released(input)],
+                    1 => vec![GamepadEvent::Pressed(input)],
+                    _ => Vec::new(),
+                }
+            }
+            EV_ABS => match event.code {
+                ABS_HAT0X => self.hold(HAT_X, direction(event.value.signum() as f32, GamepadInput::Left, GamepadInput::Right)),
+                ABS_HAT0Y => self.hold(HAT_Y, direction(event.value.signum() as f32, GamepadInput::Up, GamepadInput::Down)),
+                ABS_X => self.stick(STICK_X, event.value, GamepadInput::Left, GamepadInput::Right),
+                ABS_Y => self.stick(STICK_Y, event.value, GamepadInput::Up, GamepadInput::Down),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    fn stick(&mut self, axis: usize, value: i32, negative: GamepadInput, positive: GamepadInput) -> Vec<GamepadEvent> {
+        let range = &mut self.stick_ranges[axis - STICK_X];
+        if range.is_none() && !(0..=255).contains(&value) {
+            *range = Some((i16::MIN as i32, i16::MAX as i32));
+        }
+        let (minimum, maximum) = range.unwrap_or((0, 255));
+        let centre = (minimum as f32 + maximum as f32) / 2.0;
+        let deflection = (value as f32 - centre) / (maximum as f32 - centre);
+        // Between the two thresholds the stick keeps what it held
+        if deflection.abs() > STICK_PRESS {
+            self.hold(axis, direction(deflection, negative, positive))
+        } else if deflection.abs() < STICK_RELEASE {
+            self.hold(axis, None)
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn hold(&mut self, axis: usize, input: Option<GamepadInput>) -> Vec<GamepadEvent> {
+        let previous = std::mem::replace(&mut self.held[axis], input);
+        if previous == input {
+            return Vec::new();
+        }
+        previous.map(GamepadEvent::Released).into_iter()
+            .chain(input.map(GamepadEvent::Pressed))
+            .collect()
+    }
+}
+
+fn stick_index(code: u16) -> Option<usize> {
+    match code {
+        ABS_X => Some(0),
+        ABS_Y => Some(1),
+        _ => None,
+    }
+}
+
+fn direction(value: f32, negative: GamepadInput, positive: GamepadInput) -> Option<GamepadInput> {
+    if value < 0.0 {
+        Some(negative)
+    } else if value > 0.0 {
+        Some(positive)
+    } else {
+        None
+    }
+}
+
+/// Whether a device's `capabilities/key` bitmap has a gamepad's face button.
+/// The bitmap is hex words, most significant first, one `long` each.
+pub fn has_gamepad_buttons(key_capabilities: &str) -> bool {
+    let word_bits = usize::BITS as usize;
+    let code = BTN_SOUTH as usize;
+    key_capabilities.split_whitespace()
+        .rev()
+        .nth(code / word_bits)
+        .and_then(|word| usize::from_str_radix(word, 16).ok())
+        .is_some_and(|word| word & (1 << (code % word_bits)) != 0)
+}
+
+/// Event devices of the controllers that are connected, e.g. `/dev/input/event12`
+pub fn find_gamepads() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir("/sys/class/input") else {
+        return Vec::new();
+    };
+    let mut devices: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+        .filter(|entry| {
+            fs::read_to_string(entry.path().join("device/capabilities/key"))
+                .is_ok_and(|capabilities| has_gamepad_buttons(&capabilities))
+        })
+        .map(|entry| Path::new("/dev/input").join(entry.file_name()))
+        .collect();
+    devices.sort();
+    devices
+}
+
+/// Raw events for a mock command: `up`, `down`, `left`, `right`, `a`, `b`,
+/// `lb` and `rb` press and release; `stick-x N` and `stick-y N` move the left stick
+pub fn mock_events(command: &str) -> Result<Vec<InputEvent>, String> {
+    let key = |code| Ok(vec![
+        InputEvent { kind: EV_KEY, code, value: 1 },
+        InputEvent { kind: EV_KEY, code, value: 0 },
+    ]);
+    let hat = |code, value| Ok(vec![
+        InputEvent { kind: EV_ABS, code, value },
+        InputEvent { kind: EV_ABS, code, value: 0 },
+    ]);
+    let mut words = command.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("up"), None) => hat(ABS_HAT0Y, -1),
+        (Some("down"), None) => hat(ABS_HAT0Y, 1),
+        (Some("left"), None) => hat(ABS_HAT0X, -1),
+        (Some("right"), None) => hat(ABS_HAT0X, 1),
+        (Some("a"), None) => key(BTN_SOUTH),
+        (Some("b"), None) => key(BTN_EAST),
+        (Some("lb"), None) => key(BTN_TL),
+        (Some("rb"), None) => key(BTN_TR),
+        (Some(axis @ ("stick-x" | "stick-y")), Some(value)) => {
+            let value = value.parse().map_err(|_| format!("Invalid stick value: {}", value))?;
+            let code = if axis == "stick-x" { ABS_X } else { ABS_Y };
+            Ok(vec![InputEvent { kind: EV_ABS, code, value }])
+        }
+        _ => Err(format!("Unknown mock gamepad command: {}", command)),
+    }
+}
+
+/// A controller, or the mock standing in for one
+pub struct GamepadDevice {
+    pub path: PathBuf,
+    file: File,
+    mock: bool,
+    // Bytes of an event or command line that has not fully arrived yet
+    pending: Vec<u8>,
+    translator: GamepadTranslator,
+}
+
+impl GamepadDevice {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut device = Self::new(path, File::open(path)?, false);
+        for code in [ABS_X, ABS_Y] {
+            if let Some((minimum, maximum)) = device.axis_range(code) {
+                device.translator.set_stick_range(code, minimum, maximum);
+            }
+        }
+        Ok(device)
+    }
+
+    // Asks the kernel for an axis' `struct input_absinfo` with EVIOCGABS
+    fn axis_range(&self, code: u16) -> Option<(i32, i32)> {
+        // value, minimum, maximum, fuzz, flat, resolution
+        let mut info = [0i32; 6];
+        let request = (2 << 30) | (std::mem::size_of_val(&info) << 16) | ((b'E' as usize) << 8) | (0x40 + code as usize);
+        // SAFETY: EVIOCGABS writes one input_absinfo, which `info` has room for
+        let result = unsafe { libc::ioctl(self.fd(), request as _, info.as_mut_ptr()) };
+        (result == 0).then_some((info[1], info[2]))
+    }
+
+    /// Opens the mock's FIFO, creating it first if needed. It is opened for
+    /// writing too, so opening does not wait for a writer and it stays open
+    /// between them.
+    pub fn open_mock(path: &Path) -> io::Result<Self> {
+        if !path.exists() {
+            let c_path = CString::new(path.as_os_str().as_bytes())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Path contains a NUL byte"))?;
+            // SAFETY: `c_path` is a valid NUL-terminated string that outlives the call
+            if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+                let error = io::Error::last_os_error();
+                if error.kind() != io::ErrorKind::AlreadyExists {
+                    return Err(error);
+                }
+            }
+        }
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Self::new(path, file, true))
+    }
+
+    fn new(path: &Path, file: File, mock: bool) -> Self {
+        GamepadDevice {
+            path: path.to_path_buf(),
+            file,
+            mock,
+            pending: Vec::new(),
+            translator: GamepadTranslator::default(),
+        }
+    }
+
+    pub fn fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+
+    /// Reads what the device has ready; only call once its fd is readable
+    pub fn read_events(&mut self) -> io::Result<Vec<GamepadEvent>> {
+        let mut buffer = [0u8; INPUT_EVENT_SIZE * 64];
+        let count = self.file.read(&mut buffer)?;
+        if count == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Device closed"));
+        }
+        self.pending.extend_from_slice(&buffer[..count]);
+
+        let events = if self.mock {
+            let Some(end) = self.pending.iter().rposition(|byte| *byte == b'\n') else {
+                return Ok(Vec::new());
+            };
+            let lines: Vec<u8> = self.pending.drain(..=end).collect();
+            String::from_utf8_lossy(&lines)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .flat_map(|line| mock_events(line).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    Vec::new()
+                }))
+                .collect()
+        } else {
+            let complete = self.pending.len() - self.pending.len() % INPUT_EVENT_SIZE;
+            let bytes: Vec<u8> = self.pending.drain(..complete).collect();
+            parse_input_events(&bytes)
+        };
+        Ok(events.into_iter().flat_map(|event| self.translator.translate(event)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::os::unix::fs::FileTypeExt;
+
+    fn key(code: u16, value: i32) -> InputEvent {
+        InputEvent { kind: EV_KEY, code, value }
+    }
+
+    fn abs(code: u16, value: i32) -> InputEvent {
+        InputEvent { kind: EV_ABS, code, value }
+    }
+
+    // Feeds a series of positions to one axis and collects what comes out
+    fn feed(translator: &mut GamepadTranslator, code: u16, values: &[i32]) -> Vec<Vec<GamepadEvent>> {
+        values.iter().map(|value| translator.translate(abs(code, *value))).collect()
+    }
+
+    #[test]
+    fn hat_presses_and_releases_directions() {
+        let mut translator = GamepadTranslator::default();
+        assert_eq!(translator.translate(abs(ABS_HAT0Y, -1)), [GamepadEvent::Pressed(GamepadInput::Up)]);
+        assert_eq!(translator.translate(abs(ABS_HAT0Y, -1)), []);
+        // Rocking straight to the other side releases first
+        assert_eq!(
+            translator.translate(abs(ABS_HAT0Y, 1)),
+            [GamepadEvent::Released(GamepadInput::Up), GamepadEvent::Pressed(GamepadInput::Down)]
+        );
+        assert_eq!(translator.translate(abs(ABS_HAT0Y, 0)), [GamepadEvent::Released(GamepadInput::Down)]);
+        assert_eq!(translator.translate(abs(ABS_HAT0X, 1)), [GamepadEvent::Pressed(GamepadInput::Right)]);
+        assert_eq!(translator.translate(abs(ABS_HAT0X, 0)), [GamepadEvent::Released(GamepadInput::Right)]);
+    }
+
+    #[test]
+    fn dpad_buttons_press_and_release() {
+        let mut translator = GamepadTranslator::default();
+        assert_eq!(translator.translate(key(BTN_DPAD_LEFT, 1)), [GamepadEvent::Pressed(GamepadInput::Left)]);
+        assert_eq!(translator.translate(key(BTN_DPAD_LEFT, 0)), [GamepadEvent::Released(GamepadInput::Left)]);
+        assert_eq!(translator.translate(key(BTN_SOUTH, 1)), [GamepadEvent::Pressed(GamepadInput::Activate)]);
+        assert_eq!(translator.translate(key(BTN_TR, 0)), [GamepadEvent::Released(GamepadInput::NextProfile)]);
+    }
+
+    #[test]
+    fn key_repeat_is_ignored() {
+        let mut translator = GamepadTranslator::default();
+        assert_eq!(translator.translate(key(BTN_DPAD_DOWN, 2)), []);
+        assert_eq!(translator.translate(key(BTN_EAST, 2)), []);
+    }
+
+    #[test]
+    fn unknown_events_are_ignored() {
+        let mut translator = GamepadTranslator::default();
+        assert_eq!(translator.translate(key(0x13c, 1)), []);
+        assert_eq!(translator.translate(abs(0x05, 255)), []);
+        assert_eq!(translator.translate(InputEvent { kind: 0x00, code: 0, value: 0 }), []);
+    }
+
+    #[test]
+    fn narrow_stick_has_hysteresis() {
+        let mut translator = GamepadTranslator::default();
+        let events = feed(&mut translator, ABS_X, &[128, 200, 230, 200, 170, 150, 128]);
+        assert_eq!(events, [
+            vec![],
+            // 0.57 is below the press threshold
+            vec![],
+            vec![GamepadEvent::Pressed(GamepadInput::Right)],
+            // Still held between the thresholds
+            vec![],
+            vec![],
+            // 0.17 lets go
+            vec![GamepadEvent::Released(GamepadInput::Right)],
+            vec![],
+        ]);
+        assert_eq!(translator.translate(abs(ABS_Y, 10)), [GamepadEvent::Pressed(GamepadInput::Up)]);
+    }
+
+    #[test]
+    fn wide_stick_has_hysteresis() {
+        let mut translator = GamepadTranslator::default();
+        translator.set_stick_range(ABS_Y, -32768, 32767);
+        let events = feed(&mut translator, ABS_Y, &[0, 25000, 15000, 8000, -30000, -12000]);
+        assert_eq!(events, [
+            vec![],
+            vec![GamepadEvent::Pressed(GamepadInput::Down)],
+            vec![],
+            vec![GamepadEvent::Released(GamepadInput::Down)],
+            vec![GamepadEvent::Pressed(GamepadInput::Up)],
+            vec![],
+        ]);
+    }
+
+    #[test]
+    fn stick_ranges_from_the_device_are_used() {
+        let mut translator = GamepadTranslator::default();
+        translator.set_stick_range(ABS_X, 0, 1023);
+        // 300 would be a wide push right on a 0..=255 stick
+        assert_eq!(translator.translate(abs(ABS_X, 300)), []);
+        assert_eq!(translator.translate(abs(ABS_X, 100)), [GamepadEvent::Pressed(GamepadInput::Left)]);
+        // A range that makes no sense is ignored
+        translator.set_stick_range(ABS_Y, 5, 5);
+        assert_eq!(translator.translate(abs(ABS_Y, 250)), [GamepadEvent::Pressed(GamepadInput::Down)]);
+    }
+
+    #[test]
+    fn wide_stick_values_inside_the_narrow_range_stay_signed() {
+        let mut translator = GamepadTranslator::default();
+        translator.translate(abs(ABS_X, -300));
+        // 200 out of 32767 is centred, not pushed right as it would be on a 0..=255 stick
+        assert_eq!(translator.translate(abs(ABS_X, 200)), []);
+        assert_eq!(translator.translate(abs(ABS_X, 30000)), [GamepadEvent::Pressed(GamepadInput::Right)]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn gamepad_buttons_in_capability_bitmaps() {
+        // An Xbox controller, a keyboard and a mouse as found in sysfs
+        assert!(has_gamepad_buttons("7cdb000000000000 0 0 0 0\n"));
+        assert!(!has_gamepad_buttons("1000000000007 ff9f207ac14057ff febeffdfffefffff fffffffffffffffe\n"));
+        assert!(!has_gamepad_buttons("1f0000 0 0 0 0\n"));
+        assert!(!has_gamepad_buttons("0\n"));
+        assert!(!has_gamepad_buttons("zz 0 0 0 0"));
+    }
+
+    #[test]
+    fn parses_packed_input_events() {
+        let mut bytes = Vec::new();
+        for (kind, code, value) in [(EV_KEY, BTN_SOUTH, 1), (EV_ABS, ABS_Y, -32768), (0u16, 0u16, 0i32)] {
+            bytes.extend([0xaa; INPUT_EVENT_SIZE - 8]);
+            bytes.extend(kind.to_ne_bytes());
+            bytes.extend(code.to_ne_bytes());
+            bytes.extend(value.to_ne_bytes());
+        }
+        // An event that has not fully arrived is left out
+        bytes.extend([0u8; 5]);
+
+        assert_eq!(parse_input_events(&bytes), [
+            key(BTN_SOUTH, 1),
+            abs(ABS_Y, -32768),
+            InputEvent { kind: 0, code: 0, value: 0 },
+        ]);
+    }
+
+    #[test]
+    fn mock_commands() {
+        assert_eq!(mock_events("a").unwrap(), [key(BTN_SOUTH, 1), key(BTN_SOUTH, 0)]);
+        assert_eq!(mock_events("  up ").unwrap(), [abs(ABS_HAT0Y, -1), abs(ABS_HAT0Y, 0)]);
+        assert_eq!(mock_events("stick-x -20000").unwrap(), [abs(ABS_X, -20000)]);
+    }
+
+    #[test]
+    fn mock_command_errors() {
+        assert_eq!(mock_events("jump").unwrap_err(), "Unknown mock gamepad command: jump");
+        assert_eq!(mock_events("").unwrap_err(), "Unknown mock gamepad command: ");
+        assert_eq!(mock_events("a b").unwrap_err(), "Unknown mock gamepad command: a b");
+        assert_eq!(mock_events("stick-x").unwrap_err(), "Unknown mock gamepad command: stick-x");
+        assert_eq!(mock_events("stick-y far").unwrap_err(), "Invalid stick value: far");
+    }
+
+    #[test]
+    fn mock_device_reads_commands_from_a_fifo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gamepad");
+        let mut device = GamepadDevice::open_mock(&path).unwrap();
+        assert!(fs::metadata(&path).unwrap().file_type().is_fifo());
+
+        let mut writer = OpenOptions::new().write(true).open(&path).unwrap();
+        writer.write_all(b"down\nrb\nri").unwrap();
+        assert_eq!(device.read_events().unwrap(), [
+            GamepadEvent::Pressed(GamepadInput::Down),
+            GamepadEvent::Released(GamepadInput::Down),
+            GamepadEvent::Pressed(GamepadInput::NextProfile),
+            GamepadEvent::Released(GamepadInput::NextProfile),
+        ]);
+        // The rest of a line waits for its newline
+        writer.write_all(b"ght\n").unwrap();
+        assert_eq!(device.read_events().unwrap(), [
+            GamepadEvent::Pressed(GamepadInput::Right),
+            GamepadEvent::Released(GamepadInput::Right),
+        ]);
+    }
+}
@@ -0,0 +1,220 @@
+//! Gamepad navigation for couch use
+//! The D-pad and left stick move keyboard focus, A activates the focused
+//! widget, B goes back and the shoulder buttons switch profiles. Input is
+//! only acted on while one of our windows is active, so a controller used in
+//! a game does not drive the UI in the background.
+
+use gtk::prelude::*;
+use gtk::glib;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::app_state::AppState;
+use crate::gamepad::{find_gamepads, GamepadDevice, GamepadEvent, GamepadInput, MOCK_GAMEPAD_ENV};
+
+/// How often newly connected controllers are looked for
+const RESCAN_INTERVAL_SECONDS: u32 = 3;
+/// A held direction repeats after `REPEAT_DELAY_TICKS` ticks, then every tick
+const REPEAT_TICK: Duration = Duration::from_millis(100);
+const REPEAT_DELAY_TICKS: u32 = 4;
+
+struct GamepadNavigation {
+    app_state: Rc<RefCell<AppState>>,
+    // Devices being read, so a rescan does not open them twice
+    open_devices: RefCell<HashSet<PathBuf>>,
+    // Devices that could not be opened; retried every scan but reported once
+    failed_devices: RefCell<HashSet<PathBuf>>,
+    // The held direction and the timer repeating it
+    repeat: RefCell<Option<(GamepadInput, glib::SourceId)>>,
+}
+
+impl GamepadNavigation {
+    // Opens controllers connected since the last scan
+    fn scan(self: &Rc<Self>) {
+        let gamepads = find_gamepads();
+        // A controller plugged in again is reported afresh
+        self.failed_devices.borrow_mut().retain(|path| gamepads.contains(path));
+        for path in gamepads {
+            if self.open_devices.borrow().contains(&path) {
+                continue;
+            }
+            match GamepadDevice::open(&path) {
+                Ok(device) => {
+                    self.failed_devices.borrow_mut().remove(&path);
+                    self.watch(device);
+                }
+                // Usually a permission problem, which a udev rule may fix while we run
+                Err(e) => {
+                    if self.failed_devices.borrow_mut().insert(path.clone()) {
+                        eprintln!("Cannot read gamepad {}: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+    }
+
+    fn watch(self: &Rc<Self>, mut device: GamepadDevice) {
+        println!("Reading gamepad: {:?}", device.path);
+        self.open_devices.borrow_mut().insert(device.path.clone());
+        let navigation = self.clone();
+        glib::source::unix_fd_add_local(
+            device.fd(),
+            glib::IOCondition::IN | glib::IOCondition::HUP | glib::IOCondition::ERR,
+            move |_, condition| {
+                let events = if condition.contains(glib::IOCondition::IN) {
+                    device.read_events()
+                } else {
+                    Err(std::io::Error::other("Device disconnected"))
+                };
+                match events {
+                    Ok(events) => {
+                        for event in events {
+                            navigation.handle(event);
+                        }
+                        glib::ControlFlow::Continue
+                    }
+                    Err(e) => {
+                        println!("Stopped reading gamepad {:?}: {}", device.path, e);
+                        navigation.open_devices.borrow_mut().remove(&device.path);
+                        navigation.stop_repeat();
+                        glib::ControlFlow::Break
+                    }
+                }
+            },
+        );
+    }
+
+    fn handle(self: &Rc<Self>, event: GamepadEvent) {
+        let Some(window) = active_window() else {
+            self.stop_repeat();
+            return;
+        };
+        match event {
+            GamepadEvent::Pressed(input) if input.is_direction() => {
+                self.navigate(&window, input);
+                self.start_repeat(input);
+            }
+            GamepadEvent::Pressed(GamepadInput::Activate) => activate_focus(&window),
+            GamepadEvent::Pressed(GamepadInput::Back) => self.back(&window),
+            GamepadEvent::Pressed(GamepadInput::PreviousProfile) => self.app_state.borrow().select_adjacent_profile(-1),
+            GamepadEvent::Pressed(GamepadInput::NextProfile) => self.app_state.borrow().select_adjacent_profile(1),
+            GamepadEvent::Pressed(_) => {}
+            GamepadEvent::Released(input) => {
+                if self.repeat.borrow().as_ref().is_some_and(|(held, _)| *held == input) {
+                    self.stop_repeat();
+                }
+            }
+        }
+    }
+
+    // Moves focus like the arrow keys, showing where it is
+    fn navigate(&self, window: &gtk::Window, input: GamepadInput) {
+        window.add_css_class("gamepad-navigation");
+        window.set_focus_visible(true);
+        let direction = match input {
+            GamepadInput::Up => gtk::DirectionType::Up,
+            GamepadInput::Down => gtk::DirectionType::Down,
+            GamepadInput::Left => gtk::DirectionType::Left,
+            _ => gtk::DirectionType::Right,
+        };
+        // An open popover, such as a dropdown's list, keeps focus to itself
+        let focus = gtk::prelude::RootExt::focus(window);
+        let scope: gtk::Widget = focus.as_ref()
+            .and_then(|focus| focus.ancestor(gtk::Popover::static_type()))
+            .unwrap_or_else(|| window.clone().upcast());
+        if focus.is_none() {
+            scope.child_focus(gtk::DirectionType::TabForward);
+        } else {
+            scope.child_focus(direction);
+        }
+    }
+
+    fn start_repeat(self: &Rc<Self>, input: GamepadInput) {
+        self.stop_repeat();
+        let navigation = self.clone();
+        let mut ticks = 0;
+        let source = glib::timeout_add_local(REPEAT_TICK, move || {
+            ticks += 1;
+            if ticks > REPEAT_DELAY_TICKS {
+                match active_window() {
+                    Some(window) => navigation.navigate(&window, input),
+                    None => {
+                        navigation.repeat.borrow_mut().take();
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+            glib::ControlFlow::Continue
+        });
+        *self.repeat.borrow_mut() = Some((input, source));
+    }
+
+    fn stop_repeat(&self) {
+        if let Some((_, source)) = self.repeat.borrow_mut().take() {
+            source.remove();
+        }
+    }
+
+    // Closes an open popover or dialog, or returns to the profile list in the narrow layout
+    fn back(&self, window: &gtk::Window) {
+        let popover = gtk::prelude::RootExt::focus(window)
+            .and_then(|focus| focus.ancestor(gtk::Popover::static_type()))
+            .and_downcast::<gtk::Popover>();
+        if let Some(popover) = popover {
+            popover.popdown();
+            return;
+        }
+
+        let state = self.app_state.borrow();
+        if *window != *state.main_window.upcast_ref::<gtk::Window>() {
+            drop(state);
+            window.close();
+        } else if state.split_view.is_collapsed() && state.split_view.shows_content() {
+            state.split_view.set_show_content(false);
+            if let Some(row) = state.selected_profile_index.and_then(|index| state.sidebar_rows.borrow().get(index).cloned()) {
+                row.grab_focus();
+            }
+        }
+    }
+}
+
+fn active_window() -> Option<gtk::Window> {
+    gtk::Window::list_toplevels().into_iter()
+        .filter_map(|widget| widget.downcast::<gtk::Window>().ok())
+        .find(|window| window.is_active())
+}
+
+// Presses the focused widget the way Enter or Space would
+fn activate_focus(window: &gtk::Window) {
+    if let Some(focus) = gtk::prelude::RootExt::focus(window) {
+        if !focus.activate() {
+            focus.mnemonic_activate(false);
+        }
+    }
+}
+
+/// Starts reading controllers, and the mock device if `LSFG_VK_UI_MOCK_GAMEPAD` names one
+pub fn start_gamepad_navigation(app_state: &Rc<RefCell<AppState>>) {
+    let navigation = Rc::new(GamepadNavigation {
+        app_state: app_state.clone(),
+        open_devices: RefCell::new(HashSet::new()),
+        failed_devices: RefCell::new(HashSet::new()),
+        repeat: RefCell::new(None),
+    });
+
+    if let Some(mock_path) = std::env::var_os(MOCK_GAMEPAD_ENV) {
+        match GamepadDevice::open_mock(Path::new(&mock_path)) {
+            Ok(device) => navigation.watch(device),
+            Err(e) => eprintln!("Cannot open mock gamepad {:?}: {}", mock_path, e),
+        }
+    }
+
+    navigation.scan();
+    glib::timeout_add_seconds_local(RESCAN_INTERVAL_SECONDS, move || {
+        navigation.scan();
+        glib::ControlFlow::Continue
+    });
+}
@@ -0,0 +1,154 @@
+//! Gamescope integration
+//! Optional per-profile gamescope settings that are turned into a command
+//! prefix (`gamescope … --`) for every generated launch command, plus checks
+//! for combinations that do not work well together with lsfg-vk.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameProfile;
+use crate::profile_meta::ProfileMeta;
+
+/// Gamescope's `-F` filters plus its integer scaler
+pub const UPSCALERS: [(&str, &str); 6] = [
+    ("", "None"),
+    ("fsr", "AMD FSR"),
+    ("nis", "NVIDIA NIS"),
+    ("linear", "Linear"),
+    ("nearest", "Nearest"),
+    ("integer", "Integer"),
+];
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GamescopeSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_rate: Option<u32>,
+    /// One of the keys in `UPSCALERS`; empty for none
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub upscaler: String,
+    /// Explicit HDR setting; `None` follows the profile's `hdr_mode`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdr: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fps_limit: Option<u32>,
+}
+
+impl GamescopeSettings {
+    /// Whether gamescope runs with HDR for the given profile
+    pub fn hdr_enabled(&self, profile: &GameProfile) -> bool {
+        self.hdr.unwrap_or(profile.hdr_mode)
+    }
+
+    /// Arguments for gamescope, ending with the `--` that precedes the game command
+    pub fn build_args(&self, profile: &GameProfile) -> Vec<String> {
+        let mut args = vec!["gamescope".to_string()];
+        let mut push = |flag: &str, value: Option<u32>| {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value.to_string());
+            }
+        };
+        push("-W", self.output_width);
+        push("-H", self.output_height);
+        push("-w", self.internal_width);
+        push("-h", self.internal_height);
+        push("-r", self.refresh_rate);
+        push("--framerate-limit", self.fps_limit);
+
+        match self.upscaler.as_str() {
+            "" => {}
+            "integer" => args.extend(["-S".to_string(), "integer".to_string()]),
+            filter => args.extend(["-F".to_string(), filter.to_string()]),
+        }
+        if self.hdr_enabled(profile) {
+            args.push("--hdr-enabled".to_string());
+        }
+        args.push("--".to_string());
+        args
+    }
+
+    /// Describes combinations that conflict with each other or with the lsfg-vk profile
+    pub fn conflicts(&self, profile: &GameProfile) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.enabled {
+            return warnings;
+        }
+
+        match (profile.hdr_mode, self.hdr_enabled(profile)) {
+            (true, false) => warnings.push("HDR is on in lsfg-vk but off in gamescope".to_string()),
+            (false, true) => warnings.push("HDR is on in gamescope but off in lsfg-vk".to_string()),
+            _ => {}
+        }
+        if self.output_width.is_some() != self.output_height.is_some() {
+            warnings.push("Output resolution needs both a width and a height".to_string());
+        }
+        if self.internal_width.is_some() != self.internal_height.is_some() {
+            warnings.push("Internal resolution needs both a width and a height".to_string());
+        }
+        if let (Some(internal), Some(output)) = (self.internal_width, self.output_width) {
+            if !self.upscaler.is_empty() && internal >= output {
+                warnings.push("The upscaler has no effect unless the internal resolution is smaller than the output".to_string());
+            }
+        }
+        if let (Some(limit), Some(refresh)) = (self.fps_limit, self.refresh_rate) {
+            let output_fps = limit * profile.multiplier.max(1);
+            if output_fps > refresh {
+                warnings.push(format!(
+                    "{} FPS × {} frame generation gives {} FPS, above the {} Hz refresh rate",
+                    limit, profile.multiplier, output_fps, refresh
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+/// The gamescope prefix for a profile's launch commands; empty when gamescope is off
+pub fn gamescope_prefix(meta: Option<&ProfileMeta>, profile: &GameProfile) -> Vec<String> {
+    meta.and_then(|meta| meta.gamescope.as_ref())
+        .filter(|settings| settings.enabled)
+        .map(|settings| settings.build_args(profile))
+        .unwrap_or_default()
+}
+
+/// Parses "1920x1080"; an empty string means "not set"
+pub fn parse_resolution(text: &str) -> Result<(Option<u32>, Option<u32>), String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok((None, None));
+    }
+    let (width, height) = text.split_once(['x', 'X', '×'])
+        .ok_or_else(|| format!("'{}' is not a resolution like 1920x1080", text))?;
+    let parse = |value: &str| value.trim().parse::<u32>()
+        .ok()
+        .filter(|value| *value > 0)
+        .ok_or_else(|| format!("'{}' is not a resolution like 1920x1080", text));
+    Ok((Some(parse(width)?), Some(parse(height)?)))
+}
+
+pub fn format_resolution(width: Option<u32>, height: Option<u32>) -> String {
+    match (width, height) {
+        (Some(width), Some(height)) => format!("{}x{}", width, height),
+        _ => String::new(),
+    }
+}
+
+/// Parses an optional positive number; empty or 0 means "not set"
+pub fn parse_optional_number(text: &str, what: &str) -> Result<Option<u32>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse::<u32>()
+        .map(|value| (value > 0).then_some(value))
+        .map_err(|_| format!("{} must be a whole number", what))
+}
@@ -0,0 +1,71 @@
+//! Declarative table of lsfg-vk's `[global]` options
+//! The global settings page is generated from `GLOBAL_OPTIONS`; supporting a
+//! new key takes a field on `GlobalConfig` and one entry here.
+
+use crate::config::GlobalConfig;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlobalValue {
+    Bool(bool),
+    Text(String),
+}
+
+pub struct GlobalOption {
+    /// Key in the `[global]` table
+    pub key: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// What lsfg-vk uses when the key is absent
+    pub default: GlobalValue,
+    /// Placeholder shown in text rows while the default applies
+    pub placeholder: &'static str,
+    pub get: fn(&GlobalConfig) -> Option<GlobalValue>,
+    pub set: fn(&mut GlobalConfig, Option<GlobalValue>),
+}
+
+fn text_value(value: Option<GlobalValue>) -> Option<String> {
+    match value {
+        Some(GlobalValue::Text(text)) if !text.trim().is_empty() => Some(text),
+        _ => None,
+    }
+}
+
+fn bool_value(value: Option<GlobalValue>) -> Option<bool> {
+    match value {
+        Some(GlobalValue::Bool(value)) => Some(value),
+        _ => None,
+    }
+}
+
+pub const GLOBAL_OPTIONS: &[GlobalOption] = &[
+    GlobalOption {
+        key: "dll",
+        title: "Lossless.dll Path",
+        description: "Custom location of Lossless.dll. Leave empty to let lsfg-vk find it in your Steam library.",
+        default: GlobalValue::Text(String::new()),
+        placeholder: "Detected automatically",
+        get: |global| global.dll.clone().map(GlobalValue::Text),
+        set: |global, value| global.dll = text_value(value),
+    },
+    GlobalOption {
+        key: "no_fp16",
+        title: "Disable FP16",
+        description: "Force 32-bit floating point shaders. Helps older NVIDIA cards that are slow or broken with FP16.",
+        default: GlobalValue::Bool(false),
+        placeholder: "",
+        get: |global| global.no_fp16.map(GlobalValue::Bool),
+        set: |global, value| global.no_fp16 = bool_value(value),
+    },
+];
+
+impl GlobalOption {
+    /// The value in effect: the configured one or lsfg-vk's default
+    pub fn effective_value(&self, global: Option<&GlobalConfig>) -> GlobalValue {
+        global.and_then(self.get).unwrap_or_else(|| self.default.clone())
+    }
+
+    /// True if the key is written to conf.toml rather than left to lsfg-vk's default
+    pub fn is_set(&self, global: Option<&GlobalConfig>) -> bool {
+        global.and_then(self.get).is_some()
+    }
+}
@@ -0,0 +1,250 @@
+//! GPU enumeration for per-profile GPU selection
+//! GPUs are read from `<sysfs root>/class/drm/card*` and named with the
+//! bundled pci.ids subset. The sysfs root is a parameter so a fake tree can
+//! stand in for /sys; `LSFG_VK_UI_SYSFS_ROOT` points the UI at one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const PCI_IDS: &str = include_str!("../resources/pci.ids");
+
+pub const DEFAULT_SYSFS_ROOT: &str = "/sys";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuDevice {
+    /// DRM card node, e.g. "card1"
+    pub card: String,
+    /// PCI bus ID, e.g. "03:00.0", when sysfs reveals it
+    pub pci_slot: Option<String>,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// True for the GPU the firmware booted with
+    pub boot_vga: bool,
+    pub name: String,
+}
+
+impl GpuDevice {
+    /// Value stored in the profile's `gpu` key: the PCI bus ID, which stays
+    /// stable when card numbering changes between boots and tells two
+    /// identical GPUs apart. Falls back to the vendor and device ID as `vvvv:dddd`.
+    pub fn selection_key(&self) -> String {
+        match &self.pci_slot {
+            Some(slot) => slot.clone(),
+            None => format!("{:04x}:{:04x}", self.vendor_id, self.device_id),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        if self.boot_vga {
+            format!("{} ({}, primary)", self.name, self.card)
+        } else {
+            format!("{} ({})", self.name, self.card)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PciVendor {
+    id: u16,
+    name: String,
+    devices: Vec<(u16, String)>,
+}
+
+#[derive(Debug, Default)]
+struct PciIds {
+    vendors: Vec<PciVendor>,
+}
+
+fn pci_ids() -> &'static PciIds {
+    static IDS: OnceLock<PciIds> = OnceLock::new();
+    IDS.get_or_init(|| parse_pci_ids(PCI_IDS))
+}
+
+fn parse_pci_ids(contents: &str) -> PciIds {
+    let mut ids = PciIds::default();
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let is_device = line.starts_with('\t');
+        let Some((id, name)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        let Ok(id) = u16::from_str_radix(id, 16) else {
+            continue;
+        };
+        let name = name.trim().to_string();
+        if is_device {
+            if let Some(vendor) = ids.vendors.last_mut() {
+                vendor.devices.push((id, name));
+            }
+        } else {
+            ids.vendors.push(PciVendor { id, name, devices: Vec::new() });
+        }
+    }
+    ids
+}
+
+/// Resolves a PCI vendor/device pair to a readable name
+pub fn gpu_name(vendor_id: u16, device_id: u16) -> String {
+    let Some(vendor) = pci_ids().vendors.iter().find(|vendor| vendor.id == vendor_id) else {
+        return format!("Unknown GPU {:04x}:{:04x}", vendor_id, device_id);
+    };
+    match vendor.devices.iter().find(|(id, _)| *id == device_id) {
+        Some((_, device_name)) => device_name.clone(),
+        None => format!("{} device {:04x}", vendor.name, device_id),
+    }
+}
+
+/// The sysfs root to use: `LSFG_VK_UI_SYSFS_ROOT` if set, otherwise /sys
+pub fn sysfs_root() -> PathBuf {
+    std::env::var_os("LSFG_VK_UI_SYSFS_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SYSFS_ROOT))
+}
+
+fn read_hex_id(path: &Path) -> Option<u16> {
+    let text = fs::read_to_string(path).ok()?;
+    let text = text.trim();
+    u16::from_str_radix(text.strip_prefix("0x").unwrap_or(text), 16).ok()
+}
+
+// The bus ID from the device's uevent, or from the PCI directory the `device`
+// link points to. The usual "0000:" domain is left out, as lsfg-vk writes it.
+fn read_pci_slot(device_dir: &Path) -> Option<String> {
+    let from_uevent = fs::read_to_string(device_dir.join("uevent")).ok().and_then(|uevent| {
+        uevent.lines().find_map(|line| line.strip_prefix("PCI_SLOT_NAME=").map(str::to_string))
+    });
+    let slot = from_uevent.or_else(|| {
+        let target = fs::read_link(device_dir).ok()?;
+        Some(target.file_name()?.to_string_lossy().into_owned())
+    })?;
+    let slot = slot.trim();
+    Some(slot.strip_prefix("0000:").unwrap_or(slot).to_string())
+}
+
+/// Lists the GPUs under `<sysfs_root>/class/drm`, ordered by card number.
+/// Connector nodes such as "card0-DP-1" and render nodes are skipped.
+pub fn enumerate_gpus(sysfs_root: &Path) -> Vec<GpuDevice> {
+    let Ok(entries) = fs::read_dir(sysfs_root.join("class/drm")) else {
+        return Vec::new();
+    };
+
+    let mut gpus: Vec<(u32, GpuDevice)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let card = entry.file_name().to_string_lossy().into_owned();
+            let number: u32 = card.strip_prefix("card")?.parse().ok()?;
+            let device_dir = entry.path().join("device");
+            let vendor_id = read_hex_id(&device_dir.join("vendor"))?;
+            let device_id = read_hex_id(&device_dir.join("device"))?;
+            let boot_vga = fs::read_to_string(device_dir.join("boot_vga"))
+                .is_ok_and(|value| value.trim() == "1");
+            Some((number, GpuDevice {
+                card,
+                pci_slot: read_pci_slot(&device_dir),
+                vendor_id,
+                device_id,
+                boot_vga,
+                name: gpu_name(vendor_id, device_id),
+            }))
+        })
+        .collect();
+
+    gpus.sort_by_key(|(number, _)| *number);
+    gpus.into_iter().map(|(_, gpu)| gpu).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    fn add_card(drm: &Path, card: &str, files: &[(&str, &str)]) -> PathBuf {
+        let device_dir = drm.join(card).join("device");
+        fs::create_dir_all(&device_dir).unwrap();
+        for (name, contents) in files {
+            fs::write(device_dir.join(name), contents).unwrap();
+        }
+        device_dir
+    }
+
+    // Two identical RTX 3060s, a Radeon reached through a `device` link, a card
+    // whose IDs cannot be read, a connector and a render node
+    fn fake_sysfs() -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        let drm = root.path().join("class/drm");
+        add_card(&drm, "card0", &[
+            ("vendor", "0x10de\n"), ("device", "0x2504\n"), ("boot_vga", "1\n"),
+            ("uevent", "DRIVER=nvidia\nPCI_SLOT_NAME=0000:01:00.0\n"),
+        ]);
+        add_card(&drm, "card0-DP-1", &[("status", "connected\n")]);
+        add_card(&drm, "card1", &[
+            ("vendor", "0x10de\n"), ("device", "0x2504\n"), ("boot_vga", "0\n"),
+            ("uevent", "DRIVER=nvidia\nPCI_SLOT_NAME=0000:02:00.0\n"),
+        ]);
+        let unreadable = add_card(&drm, "card2", &[("boot_vga", "0\n")]);
+        fs::create_dir(unreadable.join("vendor")).unwrap();
+        fs::create_dir(unreadable.join("device")).unwrap();
+        fs::create_dir_all(drm.join("renderD128/device")).unwrap();
+
+        let pci_device = root.path().join("devices/pci0000:00/0000:0a:00.0");
+        fs::create_dir_all(&pci_device).unwrap();
+        fs::write(pci_device.join("vendor"), "0x1002\n").unwrap();
+        fs::write(pci_device.join("device"), "0x73df\n").unwrap();
+        fs::create_dir_all(drm.join("card10")).unwrap();
+        symlink(&pci_device, drm.join("card10/device")).unwrap();
+        root
+    }
+
+    #[test]
+    fn enumerates_cards_in_order() {
+        let root = fake_sysfs();
+        let gpus = enumerate_gpus(root.path());
+
+        let cards: Vec<&str> = gpus.iter().map(|gpu| gpu.card.as_str()).collect();
+        assert_eq!(cards, ["card0", "card1", "card10"]);
+        assert_eq!(gpus[0].name, "GA106 [GeForce RTX 3060 Lite Hash Rate]");
+        assert!(gpus[0].boot_vga);
+        assert!(!gpus[1].boot_vga);
+        assert_eq!(gpus[2].vendor_id, 0x1002);
+        assert_eq!(gpus[2].pci_slot.as_deref(), Some("0a:00.0"));
+    }
+
+    #[test]
+    fn identical_gpus_get_distinct_keys_and_labels() {
+        let root = fake_sysfs();
+        let gpus = enumerate_gpus(root.path());
+
+        assert_eq!((gpus[0].vendor_id, gpus[0].device_id), (gpus[1].vendor_id, gpus[1].device_id));
+        assert_eq!(gpus[0].selection_key(), "01:00.0");
+        assert_eq!(gpus[1].selection_key(), "02:00.0");
+        assert_ne!(gpus[0].label(), gpus[1].label());
+    }
+
+    #[test]
+    fn selection_key_falls_back_to_pci_ids() {
+        let gpu = GpuDevice {
+            card: "card0".to_string(),
+            pci_slot: None,
+            vendor_id: 0x10de,
+            device_id: 0x2504,
+            boot_vga: false,
+            name: gpu_name(0x10de, 0x2504),
+        };
+        assert_eq!(gpu.selection_key(), "10de:2504");
+    }
+
+    #[test]
+    fn missing_drm_class_gives_no_gpus() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(enumerate_gpus(root.path()).is_empty());
+    }
+
+    #[test]
+    fn names_unknown_devices() {
+        assert_eq!(gpu_name(0x10de, 0xffff), "NVIDIA Corporation device ffff");
+        assert_eq!(gpu_name(0xabcd, 0x0001), "Unknown GPU abcd:0001");
+    }
+}
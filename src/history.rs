@@ -0,0 +1,269 @@
+//! Config history
+//! Every save of conf.toml is recorded in `history.toml` under the XDG state
+//! directory, together with a short summary of what changed since the
+//! previous save. Snapshots
+//! can be compared field by field and restored as a whole or per profile.
+
+use serde::{Deserialize, Serialize};
+use std::{fs, io};
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::global_options::{GlobalValue, GLOBAL_OPTIONS};
+use crate::profile_fields::{FieldValue, ProfileField};
+use crate::utils::unix_time_now;
+
+/// Older snapshots are dropped once the history grows past this
+pub const MAX_SNAPSHOTS: usize = 200;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct HistoryFile {
+    #[serde(default)]
+    pub snapshot: Vec<Snapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    /// Unix time of the save
+    pub time: u64,
+    pub summary: String,
+    /// conf.toml exactly as it was written
+    pub config: String,
+}
+
+impl Snapshot {
+    pub fn parse(&self) -> Result<Config, String> {
+        toml::from_str(&self.config).map_err(|e| format!("Failed to parse snapshot: {}", e))
+    }
+
+    /// Local date and time of the save
+    pub fn time_label(&self) -> String {
+        gtk::glib::DateTime::from_unix_local(self.time as i64)
+            .and_then(|time| time.format("%Y-%m-%d %H:%M:%S"))
+            .map(|text| text.to_string())
+            .unwrap_or_else(|_| self.time.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    ProfileAdded(String),
+    ProfileRemoved(String),
+    FieldChanged { exe: String, field: ProfileField, before: FieldValue, after: FieldValue },
+    GlobalChanged { key: &'static str, before: Option<GlobalValue>, after: Option<GlobalValue> },
+    /// Profiles present on both sides are in a different order
+    Reordered,
+}
+
+fn describe_global(value: &Option<GlobalValue>) -> String {
+    match value {
+        None => "default".to_string(),
+        Some(GlobalValue::Bool(true)) => "on".to_string(),
+        Some(GlobalValue::Bool(false)) => "off".to_string(),
+        Some(GlobalValue::Text(text)) => text.clone(),
+    }
+}
+
+impl ConfigChange {
+    /// The profile a change belongs to, if any
+    pub fn profile(&self) -> Option<&str> {
+        match self {
+            ConfigChange::ProfileAdded(exe) | ConfigChange::ProfileRemoved(exe) => Some(exe),
+            ConfigChange::FieldChanged { exe, .. } => Some(exe),
+            ConfigChange::GlobalChanged { .. } | ConfigChange::Reordered => None,
+        }
+    }
+
+    /// The change without the profile it belongs to, e.g. "multiplier 2→3"
+    pub fn detail(&self) -> String {
+        match self {
+            ConfigChange::ProfileAdded(_) => "added".to_string(),
+            ConfigChange::ProfileRemoved(_) => "removed".to_string(),
+            ConfigChange::FieldChanged { field, before, after, .. } => {
+                format!("{} {}→{}", field.key(), before.describe(), after.describe())
+            }
+            ConfigChange::GlobalChanged { key, before, after } => {
+                format!("{} {}→{}", key, describe_global(before), describe_global(after))
+            }
+            ConfigChange::Reordered => "profiles reordered".to_string(),
+        }
+    }
+
+    /// e.g. "shooter.exe: multiplier 2→3"
+    pub fn describe(&self) -> String {
+        match self {
+            ConfigChange::GlobalChanged { .. } => format!("global: {}", self.detail()),
+            ConfigChange::Reordered => self.detail(),
+            _ => format!("{}: {}", self.profile().unwrap_or_default(), self.detail()),
+        }
+    }
+}
+
+/// Lists what changed from `before` to `after`; profiles are matched by name
+pub fn diff_configs(before: &Config, after: &Config) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+
+    for option in GLOBAL_OPTIONS {
+        let old = before.ordered_global.global.as_ref().and_then(option.get);
+        let new = after.ordered_global.global.as_ref().and_then(option.get);
+        if old != new {
+            changes.push(ConfigChange::GlobalChanged { key: option.key, before: old, after: new });
+        }
+    }
+
+    for profile in &before.game {
+        match after.game.iter().find(|p| p.exe == profile.exe) {
+            None => changes.push(ConfigChange::ProfileRemoved(profile.exe.clone())),
+            Some(updated) => {
+                for field in ProfileField::all().filter(|field| !field.values_equal(profile, updated)) {
+                    changes.push(ConfigChange::FieldChanged {
+                        exe: profile.exe.clone(),
+                        field,
+                        before: field.value(profile),
+                        after: field.value(updated),
+                    });
+                }
+            }
+        }
+    }
+    for profile in &after.game {
+        if !before.game.iter().any(|p| p.exe == profile.exe) {
+            changes.push(ConfigChange::ProfileAdded(profile.exe.clone()));
+        }
+    }
+
+    let common_order = |config: &Config, other: &Config| -> Vec<String> {
+        config.game.iter()
+            .filter(|profile| other.game.iter().any(|p| p.exe == profile.exe))
+            .map(|profile| profile.exe.clone())
+            .collect()
+    };
+    if common_order(before, after) != common_order(after, before) {
+        changes.push(ConfigChange::Reordered);
+    }
+    changes
+}
+
+/// Short summary of a list of changes for the history timeline
+pub fn summarize(changes: &[ConfigChange]) -> String {
+    const SHOWN: usize = 3;
+    if changes.is_empty() {
+        return "No changes".to_string();
+    }
+    let mut summary = changes.iter()
+        .take(SHOWN)
+        .map(ConfigChange::describe)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if changes.len() > SHOWN {
+        summary.push_str(&format!(" and {} more", changes.len() - SHOWN));
+    }
+    summary
+}
+
+// --- History File Handling Functions ---
+
+pub fn get_history_path() -> Result<PathBuf, io::Error> {
+    let state_dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Could not find state directory"))?
+        .join("lsfg-vk-ui");
+    fs::create_dir_all(&state_dir)?;
+    Ok(state_dir.join("history.toml"))
+}
+
+pub fn load_history() -> Result<HistoryFile, io::Error> {
+    let path = get_history_path()?;
+    if !path.exists() {
+        return Ok(HistoryFile::default());
+    }
+    let contents = fs::read_to_string(&path)?;
+    toml::from_str(&contents).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("Failed to parse history: {}", e))
+    })
+}
+
+pub fn save_history(history: &HistoryFile) -> Result<(), io::Error> {
+    let path = get_history_path()?;
+    let toml_string = toml::to_string_pretty(history)
+        .map_err(|e| io::Error::other(format!("Failed to serialize history: {}", e)))?;
+    fs::write(&path, toml_string)?;
+    Ok(())
+}
+
+/// Records a save of conf.toml; saving unchanged contents records nothing
+pub fn record_snapshot(config: &Config, contents: &str) -> Result<(), io::Error> {
+    let mut history = load_history()?;
+    let previous = history.snapshot.last();
+    if previous.is_some_and(|snapshot| snapshot.config == contents) {
+        return Ok(());
+    }
+
+    let summary = match previous.map(Snapshot::parse) {
+        Some(Ok(previous)) => summarize(&diff_configs(&previous, config)),
+        Some(Err(_)) => "Saved (previous snapshot unreadable)".to_string(),
+        None => format!("First recorded save, {} profiles", config.game.len()),
+    };
+    history.snapshot.push(Snapshot { time: unix_time_now(), summary, config: contents.to_string() });
+    let excess = history.snapshot.len().saturating_sub(MAX_SNAPSHOTS);
+    history.snapshot.drain(..excess);
+    save_history(&history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameProfile;
+
+    fn profile(exe: &str, multiplier: u32) -> GameProfile {
+        GameProfile { exe: exe.to_string(), multiplier, ..GameProfile::default() }
+    }
+
+    fn config(profiles: Vec<GameProfile>) -> Config {
+        Config { game: profiles, ..Config::default() }
+    }
+
+    #[test]
+    fn added_and_removed_profiles_are_listed() {
+        let before = config(vec![profile("shooter.exe", 2), profile("racer.exe", 2)]);
+        let after = config(vec![profile("shooter.exe", 2), profile("puzzle.exe", 2)]);
+        assert_eq!(diff_configs(&before, &after), vec![
+            ConfigChange::ProfileRemoved("racer.exe".to_string()),
+            ConfigChange::ProfileAdded("puzzle.exe".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn field_changes_keep_both_values() {
+        let before = config(vec![profile("shooter.exe", 2)]);
+        let after = config(vec![profile("shooter.exe", 3)]);
+        assert_eq!(diff_configs(&before, &after), vec![ConfigChange::FieldChanged {
+            exe: "shooter.exe".to_string(),
+            field: ProfileField::Multiplier,
+            before: FieldValue::Choice(Some("2".to_string())),
+            after: FieldValue::Choice(Some("3".to_string())),
+        }]);
+    }
+
+    #[test]
+    fn reordering_is_one_change() {
+        let before = config(vec![profile("shooter.exe", 2), profile("racer.exe", 2)]);
+        let after = config(vec![profile("racer.exe", 2), profile("shooter.exe", 2)]);
+        assert_eq!(diff_configs(&before, &after), vec![ConfigChange::Reordered]);
+        assert!(diff_configs(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn summary_names_the_profile_and_values() {
+        let before = config(vec![profile("shooter.exe", 2)]);
+        let after = config(vec![profile("shooter.exe", 3)]);
+        assert_eq!(summarize(&diff_configs(&before, &after)), "shooter.exe: multiplier 2→3");
+        assert_eq!(summarize(&[]), "No changes");
+
+        let changes: Vec<_> = ["a.exe", "b.exe", "c.exe", "d.exe", "e.exe"]
+            .iter()
+            .map(|exe| ConfigChange::ProfileAdded(exe.to_string()))
+            .collect();
+        assert_eq!(summarize(&changes), "a.exe: added, b.exe: added, c.exe: added and 2 more");
+    }
+}
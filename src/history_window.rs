@@ -0,0 +1,338 @@
+//! Config history window: a timeline of saves, the changes between any two
+//! snapshots and restoring the whole config or single profiles from one
+
+use gtk::prelude::*;
+use gtk::{Box, Button, DropDown, Label, ListBox, ListBoxRow, Orientation, Paned, ScrolledWindow};
+use libadwaita::prelude::*;
+use libadwaita::ApplicationWindow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::app_state::AppState;
+use crate::config::Config;
+use crate::history::{diff_configs, load_history, ConfigChange, Snapshot};
+use crate::profile_manager::show_profile_error;
+use crate::ui_components::{DialogFactory, FeedbackUtils};
+
+// The first entries of the "compared with" dropdown, before one entry per snapshot
+const BASE_PREVIOUS: u32 = 0;
+const BASE_CURRENT: u32 = 1;
+const BASE_SNAPSHOTS_START: u32 = 2;
+
+struct HistoryView {
+    app_state: Rc<RefCell<AppState>>,
+    window: ApplicationWindow,
+    // Newest first, like the timeline
+    snapshots: RefCell<Vec<Snapshot>>,
+    timeline: ListBox,
+    base_dropdown: DropDown,
+    diff_list: ListBox,
+    restore_button: Button,
+}
+
+impl HistoryView {
+    // Reloads the history file and selects the newest snapshot
+    fn reload(self: &Rc<Self>) {
+        let mut snapshots = load_history()
+            .map(|history| history.snapshot)
+            .unwrap_or_else(|e| {
+                eprintln!("Error loading config history: {}", e);
+                Vec::new()
+            });
+        snapshots.reverse();
+
+        while let Some(row) = self.timeline.row_at_index(0) {
+            self.timeline.remove(&row);
+        }
+        for snapshot in &snapshots {
+            self.timeline.append(&timeline_row(snapshot));
+        }
+
+        let mut labels = vec!["Previous snapshot".to_string(), "Current config".to_string()];
+        labels.extend(snapshots.iter().map(Snapshot::time_label));
+        let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+        *self.snapshots.borrow_mut() = snapshots;
+        self.base_dropdown.set_model(Some(&gtk::StringList::new(&label_refs)));
+        self.base_dropdown.set_selected(BASE_PREVIOUS);
+
+        self.timeline.select_row(self.timeline.row_at_index(0).as_ref());
+        self.show_diff();
+    }
+
+    fn selected_snapshot(&self) -> Option<(usize, Snapshot)> {
+        let position = self.timeline.selected_row()?.index();
+        let snapshots = self.snapshots.borrow();
+        snapshots.get(position as usize).map(|snapshot| (position as usize, snapshot.clone()))
+    }
+
+    // The config the selected snapshot is compared with
+    fn base_config(&self, position: usize) -> Result<Config, String> {
+        let snapshots = self.snapshots.borrow();
+        match self.base_dropdown.selected() {
+            BASE_PREVIOUS => snapshots.get(position + 1).map_or(Ok(Config::default()), Snapshot::parse),
+            BASE_CURRENT => Ok(self.app_state.borrow().config.clone()),
+            selected => snapshots.get((selected - BASE_SNAPSHOTS_START) as usize)
+                .ok_or_else(|| "Snapshot not found".to_string())
+                .and_then(Snapshot::parse),
+        }
+    }
+
+    // Lists the changes from the chosen base to the selected snapshot, grouped by profile
+    fn show_diff(self: &Rc<Self>) {
+        while let Some(row) = self.diff_list.row_at_index(0) {
+            self.diff_list.remove(&row);
+        }
+        let Some((position, snapshot)) = self.selected_snapshot() else {
+            self.restore_button.set_sensitive(false);
+            self.diff_list.append(&message_row("No saves recorded yet", 0));
+            return;
+        };
+        self.restore_button.set_sensitive(true);
+
+        let configs = self.base_config(position).and_then(|base| snapshot.parse().map(|selected| (base, selected)));
+        let (base, selected) = match configs {
+            Ok(configs) => configs,
+            Err(e) => {
+                self.diff_list.append(&message_row(&e, 0));
+                return;
+            }
+        };
+        let changes = diff_configs(&base, &selected);
+        if changes.is_empty() {
+            self.diff_list.append(&message_row("No differences", 0));
+            return;
+        }
+
+        let mut groups: Vec<(Option<&str>, Vec<&ConfigChange>)> = Vec::new();
+        for change in &changes {
+            match groups.iter_mut().find(|(profile, _)| *profile == change.profile()) {
+                Some((_, group)) => group.push(change),
+                None => groups.push((change.profile(), vec![change])),
+            }
+        }
+        for (profile, group) in groups {
+            let header = Box::builder()
+                .orientation(Orientation::Horizontal)
+                .spacing(6)
+                .margin_top(6)
+                .margin_bottom(6)
+                .margin_start(12)
+                .margin_end(12)
+                .build();
+            header.append(&Label::builder()
+                .label(profile.unwrap_or("General"))
+                .halign(gtk::Align::Start)
+                .hexpand(true)
+                .css_classes(["heading"])
+                .build());
+            // A profile can be restored as long as it exists in the selected snapshot
+            if let Some(restored) = profile.and_then(|exe| selected.game.iter().find(|p| p.exe == exe)) {
+                let restore_profile_button = Button::builder()
+                    .label("Restore Profile")
+                    .tooltip_text(format!("Put back '{}' as it was in this snapshot", restored.exe))
+                    .build();
+                let view = self.clone();
+                let restored = restored.clone();
+                let time = snapshot.time_label();
+                restore_profile_button.connect_clicked(move |_| {
+                    let mut state = view.app_state.borrow_mut();
+                    state.restore_profile(restored.clone());
+                    state.populate_sidebar_with_handlers(Some(view.app_state.clone()));
+                    let main_settings_box = state.main_settings_box.clone();
+                    drop(state);
+                    view.app_state.borrow().update_main_window_from_profile();
+                    FeedbackUtils::show_temporary_feedback(
+                        &main_settings_box,
+                        &format!("Restored {} from {}", restored.exe, time),
+                        3,
+                    );
+                    view.reload();
+                });
+                header.append(&restore_profile_button);
+            }
+            let header_row = ListBoxRow::builder().activatable(false).child(&header).build();
+            self.diff_list.append(&header_row);
+
+            for change in group {
+                self.diff_list.append(&message_row(&change.detail(), 24));
+            }
+        }
+    }
+
+    // Replaces the whole config with the selected snapshot after asking once
+    fn confirm_restore_config(self: &Rc<Self>) {
+        let Some((_, snapshot)) = self.selected_snapshot() else {
+            return;
+        };
+        let restored = match snapshot.parse() {
+            Ok(config) => config,
+            Err(e) => {
+                show_profile_error(&self.window, &e);
+                return;
+            }
+        };
+
+        let dialog = DialogFactory::create_confirmation_dialog(
+            &self.window,
+            "Restore Config",
+            &format!(
+                "Replace all profiles and global settings with the snapshot from {}? The current config stays in the history.",
+                snapshot.time_label()
+            ),
+            "Restore",
+        );
+        let view = self.clone();
+        dialog.connect_response(move |d, response| {
+            if response == gtk::ResponseType::Other(1) {
+                let mut state = view.app_state.borrow_mut();
+                state.restore_config(restored.clone());
+                state.populate_sidebar_with_handlers(Some(view.app_state.clone()));
+                drop(state);
+                view.app_state.borrow().update_main_window_from_profile();
+                view.reload();
+            }
+            d.close();
+        });
+        dialog.present();
+    }
+}
+
+fn timeline_row(snapshot: &Snapshot) -> ListBoxRow {
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(2)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(6)
+        .margin_end(6)
+        .build();
+    content.append(&Label::builder()
+        .label(snapshot.time_label())
+        .halign(gtk::Align::Start)
+        .css_classes(["heading"])
+        .build());
+    content.append(&Label::builder()
+        .label(&snapshot.summary)
+        .halign(gtk::Align::Start)
+        .xalign(0.0)
+        .wrap(true)
+        .lines(2)
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .css_classes(["dim-label"])
+        .build());
+    ListBoxRow::builder()
+        .tooltip_text(&snapshot.summary)
+        .child(&content)
+        .build()
+}
+
+fn message_row(message: &str, indent: i32) -> ListBoxRow {
+    let label = Label::builder()
+        .label(message)
+        .halign(gtk::Align::Start)
+        .xalign(0.0)
+        .wrap(true)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(12 + indent)
+        .margin_end(12)
+        .build();
+    ListBoxRow::builder().activatable(false).child(&label).build()
+}
+
+/// Opens the config history window
+pub fn show_history_window(app_state: Rc<RefCell<AppState>>) {
+    let main_window = app_state.borrow().main_window.clone();
+    let window = ApplicationWindow::builder()
+        .title("Config History")
+        .transient_for(&main_window)
+        .default_width(900)
+        .default_height(560)
+        .build();
+
+    let timeline = ListBox::builder()
+        .selection_mode(gtk::SelectionMode::Browse)
+        .css_classes(["navigation-sidebar"])
+        .build();
+    let timeline_scroll = ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .width_request(280)
+        .child(&timeline)
+        .build();
+
+    let base_dropdown = DropDown::builder().hexpand(true).build();
+    let compare_row = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .margin_top(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    compare_row.append(&Label::new(Some("Changes since")));
+    compare_row.append(&base_dropdown);
+
+    let diff_list = ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .valign(gtk::Align::Start)
+        .build();
+    let diff_scroll = ScrolledWindow::builder()
+        .hexpand(true)
+        .vexpand(true)
+        .child(&diff_list)
+        .build();
+
+    let restore_button = Button::builder()
+        .label("Restore Whole Config…")
+        .css_classes(["destructive-action"])
+        .build();
+    let close_button = Button::with_label("Close");
+    let footer = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .halign(gtk::Align::End)
+        .margin_bottom(12)
+        .margin_end(12)
+        .build();
+    footer.append(&restore_button);
+    footer.append(&close_button);
+
+    let details = Box::builder().orientation(Orientation::Vertical).build();
+    details.append(&compare_row);
+    details.append(&diff_scroll);
+    details.append(&footer);
+
+    let paned = Paned::builder()
+        .orientation(Orientation::Horizontal)
+        .start_child(&timeline_scroll)
+        .end_child(&details)
+        .shrink_start_child(false)
+        .build();
+    window.set_content(Some(&paned));
+
+    let view = Rc::new(HistoryView {
+        app_state,
+        window: window.clone(),
+        snapshots: RefCell::new(Vec::new()),
+        timeline: timeline.clone(),
+        base_dropdown: base_dropdown.clone(),
+        diff_list,
+        restore_button: restore_button.clone(),
+    });
+    view.reload();
+
+    let view_clone = view.clone();
+    timeline.connect_row_selected(move |_, _| view_clone.show_diff());
+    let view_clone = view.clone();
+    base_dropdown.connect_selected_notify(move |_| view_clone.show_diff());
+    let view_clone = view.clone();
+    restore_button.connect_clicked(move |_| view_clone.confirm_restore_config());
+    let window_clone = window.clone();
+    close_button.connect_clicked(move |_| window_clone.close());
+
+    window.present();
+}
@@ -0,0 +1,503 @@
+//! Launch settings dialog and the log window of a running game
+
+use gtk::prelude::*;
+use gtk::{glib, Box, Button, Label, Orientation, ScrolledWindow, TextView};
+use libadwaita::prelude::*;
+use libadwaita::ApplicationWindow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc::TryRecvError;
+use std::time::Duration;
+
+use crate::app_state::AppState;
+use crate::launcher::{launch_game, parse_env_assignments, LaunchEvent, LaunchHandle, LaunchSettings};
+use crate::profile_manager::show_profile_error;
+use crate::ui_components::{DialogFactory, LayoutFactory};
+use crate::gamescope::{
+    format_resolution, gamescope_prefix, parse_optional_number, parse_resolution, GamescopeSettings, UPSCALERS,
+};
+use crate::desktop_launcher::{
+    create_launcher, remove_launcher, remove_launcher_file, wrapper_script_path, LauncherFiles,
+};
+use crate::steam::{
+    find_localconfig, find_steam_root, generate_launch_options, is_steam_running, is_valid_app_id,
+    read_launch_options, write_launch_options,
+};
+
+const RESPONSE_SAVE: gtk::ResponseType = gtk::ResponseType::Other(1);
+const RESPONSE_SAVE_AND_LAUNCH: gtk::ResponseType = gtk::ResponseType::Other(2);
+
+/// Shows the dialog that edits the command, working directory and extra environment of a profile
+pub fn show_launch_settings_dialog(app_state: Rc<RefCell<AppState>>, profile_index: usize) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let Some(exe) = state.config.game.get(profile_index).map(|p| p.exe.clone()) else {
+        return;
+    };
+    let current = state.profile_meta.get(&exe)
+        .and_then(|meta| meta.launch.clone())
+        .unwrap_or_default();
+    drop(state);
+
+    let env_text = current.env.join(" ");
+    let (dialog, entries) = DialogFactory::create_form_dialog(
+        &main_window,
+        "Launch Settings",
+        &format!("How to start the game for '{}'. ENABLE_LSFG=1 and LSFG_PROCESS={} are set automatically.", exe, exe),
+        &[
+            ("Command", "/path/to/game --some-flag", Some(&current.command)),
+            ("Working Directory", "Defaults to the current directory", current.working_dir.as_deref()),
+            ("Extra Environment", "DXVK_HUD=fps MANGOHUD=1", Some(&env_text)),
+        ],
+    );
+    dialog.add_button("Save", RESPONSE_SAVE);
+    dialog.add_button("Save and Launch", RESPONSE_SAVE_AND_LAUNCH);
+    dialog.set_default_response(RESPONSE_SAVE_AND_LAUNCH);
+
+    let app_state_clone = app_state.clone();
+    dialog.connect_response(move |d, response| {
+        if response == RESPONSE_SAVE || response == RESPONSE_SAVE_AND_LAUNCH {
+            let working_dir = entries[1].text().trim().to_string();
+            let settings = LaunchSettings {
+                command: entries[0].text().trim().to_string(),
+                working_dir: (!working_dir.is_empty()).then_some(working_dir),
+                env: parse_env_assignments(&entries[2].text()),
+            };
+
+            let mut state = app_state_clone.borrow_mut();
+            state.profile_meta.entry(&exe).launch = settings.is_configured().then_some(settings);
+            state.save_current_config();
+            drop(state);
+            app_state_clone.borrow().update_main_window_from_profile();
+
+            if response == RESPONSE_SAVE_AND_LAUNCH {
+                launch_profile(app_state_clone.clone(), profile_index);
+            }
+        }
+        d.close();
+    });
+
+    dialog.present();
+}
+
+/// Launches the game of a profile, asking for launch settings first if there are none
+pub fn launch_profile(app_state: Rc<RefCell<AppState>>, profile_index: usize) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let Some(exe) = state.config.game.get(profile_index).map(|p| p.exe.clone()) else {
+        return;
+    };
+    let settings = state.profile_meta.get(&exe).and_then(|meta| meta.launch.clone());
+    let prefix = gamescope_prefix(state.profile_meta.get(&exe), &state.config.game[profile_index]);
+    drop(state);
+
+    let Some(settings) = settings.filter(LaunchSettings::is_configured) else {
+        show_launch_settings_dialog(app_state, profile_index);
+        return;
+    };
+
+    match launch_game(&exe, &settings, &prefix) {
+        Ok(handle) => show_launch_log_window(&main_window, &exe, &settings, handle),
+        Err(e) => show_profile_error(&main_window, &e),
+    }
+}
+
+/// Shows the dialog that edits the gamescope settings of a profile
+pub fn show_gamescope_settings_dialog(app_state: Rc<RefCell<AppState>>, profile_index: usize) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let Some(exe) = state.config.game.get(profile_index).map(|p| p.exe.clone()) else {
+        return;
+    };
+    let current = state.profile_meta.get(&exe)
+        .and_then(|meta| meta.gamescope.clone())
+        .unwrap_or_default();
+    drop(state);
+
+    let number_text = |value: Option<u32>| value.map(|value| value.to_string()).unwrap_or_default();
+    let output_resolution = format_resolution(current.output_width, current.output_height);
+    let internal_resolution = format_resolution(current.internal_width, current.internal_height);
+    let refresh_rate = number_text(current.refresh_rate);
+    let fps_limit = number_text(current.fps_limit);
+    let (dialog, entries) = DialogFactory::create_form_dialog(
+        &main_window,
+        "Gamescope",
+        &format!("Run '{}' inside gamescope. Leave a field empty to use gamescope's default.", exe),
+        &[
+            ("Output Resolution", "e.g. 2560x1440", Some(&output_resolution)),
+            ("Internal Resolution", "e.g. 1920x1080", Some(&internal_resolution)),
+            ("Refresh Rate (Hz)", "e.g. 144", Some(&refresh_rate)),
+            ("FPS Limit", "e.g. 60", Some(&fps_limit)),
+        ],
+    );
+
+    let enabled_switch = gtk::Switch::builder()
+        .active(current.enabled)
+        .valign(gtk::Align::Center)
+        .build();
+    let upscaler_labels: Vec<&str> = UPSCALERS.iter().map(|(_, label)| *label).collect();
+    let upscaler_dropdown = gtk::DropDown::from_strings(&upscaler_labels);
+    upscaler_dropdown.set_selected(
+        UPSCALERS.iter().position(|(key, _)| *key == current.upscaler).unwrap_or(0) as u32,
+    );
+    let hdr_dropdown = gtk::DropDown::from_strings(&["Follow lsfg-vk HDR Mode", "On", "Off"]);
+    hdr_dropdown.set_selected(match current.hdr {
+        None => 0,
+        Some(true) => 1,
+        Some(false) => 2,
+    });
+
+    let options_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(6)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    options_box.append(&LayoutFactory::create_labeled_row("Enable Gamescope", &enabled_switch));
+    options_box.append(&LayoutFactory::create_labeled_row("Upscaler", &upscaler_dropdown));
+    options_box.append(&LayoutFactory::create_labeled_row("HDR", &hdr_dropdown));
+    dialog.content_area().append(&options_box);
+    dialog.add_button("Save", RESPONSE_SAVE);
+    dialog.set_default_response(RESPONSE_SAVE);
+
+    let app_state_clone = app_state.clone();
+    dialog.connect_response(move |d, response| {
+        if response == RESPONSE_SAVE {
+            let parsed = (|| -> Result<GamescopeSettings, String> {
+                let (output_width, output_height) = parse_resolution(&entries[0].text())?;
+                let (internal_width, internal_height) = parse_resolution(&entries[1].text())?;
+                Ok(GamescopeSettings {
+                    enabled: enabled_switch.is_active(),
+                    output_width,
+                    output_height,
+                    internal_width,
+                    internal_height,
+                    refresh_rate: parse_optional_number(&entries[2].text(), "Refresh rate")?,
+                    upscaler: UPSCALERS[upscaler_dropdown.selected() as usize].0.to_string(),
+                    hdr: match hdr_dropdown.selected() {
+                        1 => Some(true),
+                        2 => Some(false),
+                        _ => None,
+                    },
+                    fps_limit: parse_optional_number(&entries[3].text(), "FPS limit")?,
+                })
+            })();
+
+            match parsed {
+                Ok(settings) => {
+                    let mut state = app_state_clone.borrow_mut();
+                    state.profile_meta.entry(&exe).gamescope =
+                        (settings != GamescopeSettings::default()).then_some(settings);
+                    state.save_current_config();
+                    drop(state);
+                    app_state_clone.borrow().update_main_window_from_profile();
+                }
+                Err(e) => {
+                    show_profile_error(d, &e);
+                    return;
+                }
+            }
+        }
+        d.close();
+    });
+
+    dialog.present();
+}
+
+/// Asks whether to add a menu entry next to the wrapper script, then writes the launcher
+pub fn show_create_launcher_dialog(app_state: Rc<RefCell<AppState>>, profile_index: usize) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let Some(profile) = state.config.game.get(profile_index).cloned() else {
+        return;
+    };
+    let exe = profile.exe.clone();
+    let meta = state.profile_meta.get(&exe).cloned().unwrap_or_default();
+    let prefix = gamescope_prefix(Some(&meta), &profile);
+    drop(state);
+
+    let Some(settings) = meta.launch.filter(LaunchSettings::is_configured) else {
+        show_launch_settings_dialog(app_state, profile_index);
+        return;
+    };
+    let wrapper = match wrapper_script_path(&exe) {
+        Ok(path) => path,
+        Err(e) => {
+            show_profile_error(&main_window, &e);
+            return;
+        }
+    };
+
+    let dialog = DialogFactory::create_message_dialog(
+        &main_window,
+        "Create Launcher",
+        &format!(
+            "A wrapper script that starts '{}' with lsfg-vk enabled will be written to\n{}\n\n\
+             It is kept up to date when the profile changes.",
+            exe,
+            wrapper.display()
+        ),
+        gtk::MessageType::Question,
+    );
+    let desktop_entry_check = gtk::CheckButton::builder()
+        .label("Add to the applications menu")
+        .active(meta.launcher.as_ref().is_none_or(|files| files.desktop_entry.is_some()))
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    dialog.content_area().append(&desktop_entry_check);
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    dialog.add_button("Create", RESPONSE_SAVE);
+    dialog.set_default_response(RESPONSE_SAVE);
+
+    let app_state_clone = app_state.clone();
+    dialog.connect_response(move |d, response| {
+        if response == RESPONSE_SAVE {
+            match create_launcher(&exe, &settings, &prefix, desktop_entry_check.is_active()) {
+                Ok(files) => {
+                    let mut state = app_state_clone.borrow_mut();
+                    // Drop a menu entry the user no longer wants
+                    let previous_entry = state.profile_meta.get(&exe)
+                        .and_then(|meta| meta.launcher.as_ref())
+                        .and_then(|previous| previous.desktop_entry.clone());
+                    if let (Some(path), None) = (previous_entry, files.desktop_entry.as_ref()) {
+                        remove_launcher_file(&path);
+                    }
+                    state.profile_meta.entry(&exe).launcher = Some(files);
+                    state.save_current_config();
+                    drop(state);
+                    app_state_clone.borrow().update_main_window_from_profile();
+                }
+                Err(e) => show_profile_error(d, &e),
+            }
+        }
+        d.close();
+    });
+
+    dialog.present();
+}
+
+/// Deletes the launcher files of a profile and stops tracking them
+pub fn remove_profile_launcher(app_state: &Rc<RefCell<AppState>>, profile_index: usize) {
+    let mut state = app_state.borrow_mut();
+    let Some(exe) = state.config.game.get(profile_index).map(|p| p.exe.clone()) else {
+        return;
+    };
+    if let Some(files) = state.profile_meta.entry(&exe).launcher.take() {
+        remove_launcher(&files);
+    }
+    state.save_current_config();
+    drop(state);
+    app_state.borrow().update_main_window_from_profile();
+}
+
+/// Lists every launcher the app generated, with a remove button per profile
+pub fn show_launchers_dialog(app_state: Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let launchers: Vec<(String, LauncherFiles)> = state.profile_meta.profile.iter()
+        .filter_map(|(exe, meta)| meta.launcher.clone().map(|files| (exe.clone(), files)))
+        .collect();
+    drop(state);
+
+    let message = if launchers.is_empty() {
+        "No launchers have been created yet."
+    } else {
+        "Launchers are regenerated whenever their profile is saved."
+    };
+    let dialog = DialogFactory::create_message_dialog(&main_window, "Launchers", message, gtk::MessageType::Info);
+
+    if !launchers.is_empty() {
+        let list_box = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+        for (exe, files) in launchers {
+            let row = libadwaita::ActionRow::builder()
+                .title(exe.as_str())
+                .subtitle(files.paths().join("\n"))
+                .build();
+            let remove_button = Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text("Remove launcher")
+                .valign(gtk::Align::Center)
+                .css_classes(["flat", "circular"])
+                .build();
+            row.add_suffix(&remove_button);
+            list_box.append(&row);
+
+            let app_state_clone = app_state.clone();
+            let list_box_clone = list_box.clone();
+            let row_clone = row.clone();
+            remove_button.connect_clicked(move |_| {
+                let index = app_state_clone.borrow().config.game.iter().position(|p| p.exe == exe);
+                match index {
+                    Some(index) => remove_profile_launcher(&app_state_clone, index),
+                    None => {
+                        remove_launcher(&files);
+                        let mut state = app_state_clone.borrow_mut();
+                        state.profile_meta.entry(&exe).launcher = None;
+                        state.save_current_config();
+                    }
+                }
+                list_box_clone.remove(&row_clone);
+            });
+        }
+        dialog.content_area().append(&list_box);
+    }
+
+    dialog.add_button("Close", gtk::ResponseType::Close);
+    dialog.connect_response(|d, _| d.close());
+    dialog.present();
+}
+
+/// Confirms and writes the Steam launch options of a profile into localconfig.vdf
+pub fn show_write_steam_launch_options_dialog(app_state: Rc<RefCell<AppState>>, profile_index: usize) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let main_settings_box = state.main_settings_box.clone();
+    let Some(profile) = state.config.game.get(profile_index).cloned() else {
+        return;
+    };
+    let exe = profile.exe.clone();
+    let meta = state.profile_meta.get(&exe).cloned().unwrap_or_default();
+    drop(state);
+
+    let Some(app_id) = meta.steam_app_id.clone().filter(|id| is_valid_app_id(id)) else {
+        show_profile_error(&main_window, "Enter a numeric Steam AppID first");
+        return;
+    };
+    let Some(localconfig) = find_steam_root().and_then(|root| find_localconfig(&root)) else {
+        show_profile_error(&main_window, "Could not find Steam's localconfig.vdf");
+        return;
+    };
+    let launch_options = generate_launch_options(&exe, meta.launch.as_ref(), &gamescope_prefix(Some(&meta), &profile));
+    let current = read_launch_options(&localconfig, &app_id).ok().flatten();
+
+    let mut message = format!(
+        "Set the launch options of AppID {} to:\n\n{}\n\nin {}",
+        app_id, launch_options, localconfig.display()
+    );
+    if let Some(current) = current.filter(|current| !current.is_empty()) {
+        message.push_str(&format!("\n\nThis replaces the current options:\n{}", current));
+    }
+    if is_steam_running() {
+        message.push_str("\n\nWarning: Steam is running. It rewrites localconfig.vdf when it exits, \
+            which may discard this change. Close Steam first for a reliable result.");
+    }
+    message.push_str("\n\nA backup of the current file is kept next to it.");
+
+    let dialog = DialogFactory::create_confirmation_dialog(
+        &main_window,
+        "Write Steam Launch Options",
+        &message,
+        "Write",
+    );
+    dialog.connect_response(move |d, response| {
+        if response == gtk::ResponseType::Other(1) {
+            match write_launch_options(&localconfig, &app_id, &launch_options) {
+                Ok(backup) => {
+                    println!("Wrote Steam launch options, backup at {:?}", backup);
+                    crate::ui_components::FeedbackUtils::show_temporary_feedback(&main_settings_box, "Written to Steam!", 2);
+                }
+                Err(e) => show_profile_error(d, &e),
+            }
+        }
+        d.close();
+    });
+
+    dialog.present();
+}
+
+/// Shows a window streaming the game's output and reporting its exit status
+fn show_launch_log_window(parent: &ApplicationWindow, exe: &str, settings: &LaunchSettings, handle: LaunchHandle) {
+    let window = ApplicationWindow::builder()
+        .title(format!("Launch Log – {}", exe))
+        .transient_for(parent)
+        .default_width(700)
+        .default_height(450)
+        .build();
+
+    let text_view = TextView::builder()
+        .editable(false)
+        .cursor_visible(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .build();
+    let scrolled_window = ScrolledWindow::builder()
+        .hexpand(true)
+        .vexpand(true)
+        .margin_top(12)
+        .margin_start(12)
+        .margin_end(12)
+        .child(&text_view)
+        .build();
+
+    let status_label = Label::builder()
+        .label(format!("Running (PID {})…", handle.pid))
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .build();
+    let stop_button = Button::builder()
+        .label("Stop")
+        .css_classes(["destructive-action"])
+        .build();
+    let close_button = Button::with_label("Close");
+
+    let footer = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+    footer.append(&status_label);
+    footer.append(&stop_button);
+    footer.append(&close_button);
+
+    let content = Box::builder().orientation(Orientation::Vertical).build();
+    content.append(&scrolled_window);
+    content.append(&footer);
+    window.set_content(Some(&content));
+
+    let buffer = text_view.buffer();
+    buffer.insert(&mut buffer.end_iter(), &format!("$ {}\n", settings.command));
+
+    let handle = Rc::new(handle);
+    let handle_clone = handle.clone();
+    stop_button.connect_clicked(move |_| handle_clone.stop());
+
+    let window_clone = window.clone();
+    close_button.connect_clicked(move |_| window_clone.close());
+
+    // Poll the launcher's channel from the main loop until its output has drained
+    let text_view_clone = text_view.clone();
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        loop {
+            match handle.events.try_recv() {
+                Ok(LaunchEvent::Stdout(line)) => append_line(&text_view_clone, &line),
+                Ok(LaunchEvent::Stderr(line)) => append_line(&text_view_clone, &format!("[stderr] {}", line)),
+                Ok(LaunchEvent::Exited(status)) => {
+                    append_line(&text_view_clone, &format!("--- {} ---", status));
+                    status_label.set_text(&status);
+                    stop_button.set_sensitive(false);
+                }
+                Err(TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+            }
+        }
+    });
+
+    window.present();
+}
+
+fn append_line(text_view: &TextView, line: &str) {
+    let buffer = text_view.buffer();
+    buffer.insert(&mut buffer.end_iter(), &format!("{}\n", line));
+    let end_mark = buffer.create_mark(None, &buffer.end_iter(), false);
+    text_view.scroll_to_mark(&end_mark, 0.0, false, 0.0, 1.0);
+    buffer.delete_mark(&end_mark);
+}
@@ -0,0 +1,259 @@
+//! Launching games with lsfg-vk enabled
+//! Spawns the command stored for a profile with the lsfg-vk environment and
+//! forwards the child's output and exit status over a channel. Nothing in here
+//! touches GTK, so any script can stand in for the game.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How to start the game of a profile, stored in the profile metadata file
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LaunchSettings {
+    /// Shell command line that starts the game
+    #[serde(default)]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Extra KEY=VALUE variables exported next to the lsfg-vk ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+impl LaunchSettings {
+    pub fn is_configured(&self) -> bool {
+        !self.command.trim().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchEvent {
+    Stdout(String),
+    Stderr(String),
+    Exited(String),
+}
+
+/// A running game: events arrive on `events` until every sender is gone.
+/// Output may still follow the `Exited` event while the pipes drain.
+pub struct LaunchHandle {
+    pub pid: u32,
+    pub events: Receiver<LaunchEvent>,
+    child: Arc<Mutex<Child>>,
+}
+
+impl LaunchHandle {
+    /// Kills the game; the exit status is still reported through `events`
+    pub fn stop(&self) {
+        if let Ok(mut child) = self.child.lock() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Splits whitespace separated KEY=VALUE pairs, ignoring anything without '='
+pub fn parse_env_assignments(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter(|pair| pair.split_once('=').is_some_and(|(key, _)| !key.is_empty()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The environment lsfg-vk needs for a profile, followed by the profile's extras
+pub fn build_launch_environment(profile_exe: &str, settings: &LaunchSettings) -> Vec<(String, String)> {
+    let mut env = vec![
+        ("ENABLE_LSFG".to_string(), "1".to_string()),
+        ("LSFG_PROCESS".to_string(), profile_exe.to_string()),
+    ];
+    for pair in &settings.env {
+        if let Some((key, value)) = pair.split_once('=') {
+            env.retain(|(existing, _)| existing != key);
+            env.push((key.to_string(), value.to_string()));
+        }
+    }
+    env
+}
+
+/// Quotes a value for `sh`, leaving plain words untouched
+pub fn shell_quote(value: &str) -> String {
+    let is_plain = !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:,+@%".contains(c));
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Puts a wrapper command such as gamescope in front of the game command
+pub fn prefixed_command(prefix: &[String], command: &str) -> String {
+    let mut parts: Vec<String> = prefix.iter().map(|arg| shell_quote(arg)).collect();
+    parts.push(command.trim().to_string());
+    parts.join(" ")
+}
+
+/// Describes an exit status the way the log pane shows it
+pub fn describe_exit_status(status: &ExitStatus) -> String {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => format!("Exited with code {}", code),
+        (None, Some(signal)) => format!("Terminated by signal {}", signal),
+        _ => "Exited".to_string(),
+    }
+}
+
+/// Starts the game through `sh -c` so the stored command line may use quoting;
+/// `prefix` is put in front of the command (e.g. gamescope arguments)
+pub fn launch_game(profile_exe: &str, settings: &LaunchSettings, prefix: &[String]) -> Result<LaunchHandle, String> {
+    if !settings.is_configured() {
+        return Err("No launch command configured for this profile".to_string());
+    }
+
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(format!("exec {}", prefixed_command(prefix, &settings.command)))
+        .envs(build_launch_environment(profile_exe, settings))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = settings.working_dir.as_deref().filter(|dir| !dir.trim().is_empty()) {
+        command.current_dir(dir);
+    }
+
+    let mut child = command.spawn().map_err(|e| format!("Failed to launch game: {}", e))?;
+    let pid = child.id();
+    let (sender, events) = mpsc::channel();
+
+    // The readers drain on their own; a process the game left running may keep
+    // its output open long after the game itself has exited
+    if let Some(out) = child.stdout.take() {
+        forward_lines(out, sender.clone(), LaunchEvent::Stdout);
+    }
+    if let Some(err) = child.stderr.take() {
+        forward_lines(err, sender.clone(), LaunchEvent::Stderr);
+    }
+
+    let child = Arc::new(Mutex::new(child));
+    let waiter_child = child.clone();
+    thread::spawn(move || {
+        let status = loop {
+            let result = waiter_child.lock().map(|mut child| child.try_wait());
+            match result {
+                Ok(Ok(Some(status))) => break describe_exit_status(&status),
+                Ok(Ok(None)) => thread::sleep(Duration::from_millis(100)),
+                Ok(Err(e)) => break format!("Failed to wait for game: {}", e),
+                Err(_) => break "Lost track of the game process".to_string(),
+            }
+        };
+        let _ = sender.send(LaunchEvent::Exited(status));
+    });
+
+    Ok(LaunchHandle { pid, events, child })
+}
+
+fn forward_lines<R: Read + Send + 'static>(stream: R, sender: Sender<LaunchEvent>, wrap: fn(String) -> LaunchEvent) {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if sender.send(wrap(line)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    fn write_script(dir: &Path, body: &str) -> String {
+        let path = dir.join("game.sh");
+        fs::write(&path, format!("#!/bin/sh\n{}", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        shell_quote(&path.to_string_lossy())
+    }
+
+    // Every event until the launcher drops its senders
+    fn collect_events(handle: &LaunchHandle) -> Vec<LaunchEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = handle.events.recv_timeout(Duration::from_secs(10)) {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn launch_game_passes_environment_and_streams_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = LaunchSettings {
+            command: write_script(dir.path(), concat!(
+                "echo \"enable=$ENABLE_LSFG\"\n",
+                "echo \"process=$LSFG_PROCESS\"\n",
+                "echo \"extra=$EXTRA\"\n",
+                "echo first error >&2\n",
+                "echo second error >&2\n",
+                "exit 3\n",
+            )),
+            working_dir: None,
+            env: vec!["EXTRA=some value".to_string()],
+        };
+
+        let handle = launch_game("Game.exe", &settings, &[]).unwrap();
+        let events = collect_events(&handle);
+
+        let stdout: Vec<&str> = events.iter()
+            .filter_map(|event| match event { LaunchEvent::Stdout(line) => Some(line.as_str()), _ => None })
+            .collect();
+        let stderr: Vec<&str> = events.iter()
+            .filter_map(|event| match event { LaunchEvent::Stderr(line) => Some(line.as_str()), _ => None })
+            .collect();
+        assert_eq!(stdout, ["enable=1", "process=Game.exe", "extra=some value"]);
+        assert_eq!(stderr, ["first error", "second error"]);
+        assert!(events.contains(&LaunchEvent::Exited("Exited with code 3".to_string())));
+    }
+
+    #[test]
+    fn launch_game_reports_exit_while_output_stays_open() {
+        let dir = tempfile::tempdir().unwrap();
+        // The background sleep keeps stdout open after the game has exited
+        let settings = LaunchSettings {
+            command: write_script(dir.path(), "sleep 5 &\necho started\n"),
+            ..LaunchSettings::default()
+        };
+
+        let handle = launch_game("game", &settings, &[]).unwrap();
+        let mut events = Vec::new();
+        loop {
+            let event = handle.events.recv_timeout(Duration::from_secs(3))
+                .expect("the exit should be reported before the pipes close");
+            let exited = matches!(event, LaunchEvent::Exited(_));
+            events.push(event);
+            if exited {
+                break;
+            }
+        }
+        assert_eq!(events.last(), Some(&LaunchEvent::Exited("Exited with code 0".to_string())));
+    }
+
+    #[test]
+    fn launch_game_reports_signals() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = LaunchSettings {
+            command: write_script(dir.path(), "kill -TERM $$\n"),
+            ..LaunchSettings::default()
+        };
+
+        let handle = launch_game("game", &settings, &[]).unwrap();
+        assert_eq!(collect_events(&handle), [LaunchEvent::Exited("Terminated by signal 15".to_string())]);
+    }
+
+    #[test]
+    fn launch_game_needs_a_command() {
+        assert!(launch_game("game", &LaunchSettings::default(), &[]).is_err());
+    }
+}
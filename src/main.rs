@@ -1,7 +1,9 @@
 use gtk::prelude::*;
 use gtk::{glib, Builder};
+use libadwaita::prelude::*;
 use libadwaita::ApplicationWindow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 // Import modules
@@ -15,13 +17,43 @@ mod profile_manager;
 mod signal_handlers;
 mod styling;
 mod styles;
+mod templates;
+mod presets;
+mod default_profile;
+mod profile_fields;
+mod profile_editor;
+mod sidebar;
+mod bulk_edit;
+mod compare;
+mod history;
+mod history_window;
+mod ui_state;
+mod actions;
+mod gamepad;
+mod gamepad_navigation;
+mod profile_meta;
+mod exe_matching;
+mod launcher;
+mod launch_window;
+mod vdf;
+mod steam;
+mod desktop_launcher;
+mod gamescope;
+mod frame_math;
+mod capabilities;
+mod global_options;
+mod gpu;
 
 use config::{load_config, Config, OrderedGlobalConfig};
 use app_state::AppState;
 use ui_components::WidgetUtils;
 use signal_handlers::{
-    connect_profile_signal_handlers, connect_save_button_handler,
-    connect_sidebar_row_activated_handler, connect_create_profile_button_handler
+    connect_profile_signal_handlers,
+    connect_sidebar_selection_handler, connect_sidebar_row_activated_handler, connect_sidebar_reorder_handler, connect_sidebar_search_handlers,
+    connect_expand_patterns_button_handler, connect_match_tester_handlers,
+    connect_launch_button_handler, connect_steam_button_handlers, connect_launcher_button_handlers,
+    connect_gamescope_button_handler, connect_frame_calculator_handlers,
+    connect_sidebar_resize_handler, connect_main_window_close_handler, connect_adaptive_layout_handler
 };
 use styles::{apply_application_styles, setup_icon_theme};
 
@@ -34,14 +66,71 @@ fn main() -> glib::ExitCode {
     glib::set_application_name("LSFG-VK UI");
     glib::set_prgname(Some("lsfg-vk-ui"));
 
+    // `--action win.save` runs a named action once the window is up
+    application.add_main_option(
+        "action",
+        glib::Char::from(b'a'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Run an action, e.g. app.new-profile or win.save",
+        Some("NAME"),
+    );
+    application.add_main_option(
+        "list-actions",
+        glib::Char::from(b'l'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "List the actions --action accepts",
+        None,
+    );
+
+    let pending_action: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+    let pending_action_clone = pending_action.clone();
+    application.connect_handle_local_options(move |app, options| {
+        if options.contains("list-actions") {
+            for action in actions::ACTIONS {
+                println!("{:<24} {}", action.name, action.title);
+            }
+            return 0;
+        }
+        let Ok(Some(name)) = options.lookup::<String>("action") else {
+            return -1;
+        };
+        if actions::find_action(&name).is_none() {
+            eprintln!("Unknown action '{}'; see --list-actions", name);
+            return 1;
+        }
+
+        // An instance that is already running runs the action itself
+        if let Err(e) = app.register(gtk::gio::Cancellable::NONE) {
+            eprintln!("Failed to register application: {}", e);
+            return 1;
+        }
+        if app.is_remote() {
+            app.activate_action(actions::RUN_ACTION, Some(&name.to_variant()));
+            return 0;
+        }
+        pending_action_clone.replace(Some(name));
+        -1
+    });
+
     application.connect_startup(|_app| {
         apply_application_styles();
         setup_icon_theme();
     });
 
-    application.connect_activate(|app| {
+    application.connect_activate(move |app| {
         if let Err(e) = setup_main_window(app) {
             eprintln!("Failed to setup main window: {}", e);
+            return;
+        }
+        // Runs after the idle that initializes the UI
+        if let Some(name) = pending_action.take() {
+            let app = app.clone();
+            glib::idle_add_local(move || {
+                app.activate_action(actions::RUN_ACTION, Some(&name.to_variant()));
+                glib::ControlFlow::Break
+            });
         }
     });
 
@@ -50,7 +139,7 @@ fn main() -> glib::ExitCode {
 
 fn setup_main_window(app: &libadwaita::Application) -> Result<(), Box<dyn std::error::Error>> {
     // Load initial configuration
-    let initial_config = load_config().unwrap_or_else(|e| {
+    let mut initial_config = load_config().unwrap_or_else(|e| {
         eprintln!("Error loading config: {}", e);
         Config {
             version: 1,
@@ -58,6 +147,20 @@ fn setup_main_window(app: &libadwaita::Application) -> Result<(), Box<dyn std::e
             game: Vec::new()
         }
     });
+    default_profile::pin_default_profile(&mut initial_config);
+
+    // Load UI-only profile metadata and make sure inherited values are current
+    let profile_meta = profile_meta::load_profile_meta().unwrap_or_else(|e| {
+        eprintln!("Error loading profile metadata: {}", e);
+        profile_meta::ProfileMetaFile::default()
+    });
+    profile_meta::flatten_inheritance(&mut initial_config, &profile_meta);
+
+    // Window size, sidebar and selection as they were left last time
+    let saved_ui_state = ui_state::load_ui_state().unwrap_or_else(|e| {
+        eprintln!("Error loading UI state: {}", e);
+        ui_state::UiState::default()
+    });
 
     // Load UI from .ui file
     let ui_bytes = include_bytes!("../resources/ui.ui");
@@ -70,102 +173,300 @@ fn setup_main_window(app: &libadwaita::Application) -> Result<(), Box<dyn std::e
     widgets.main_window.set_application(Some(app));
     widgets.main_window.set_icon_name(Some("com.cali666.lsfg-vk-ui"));
 
-    // Create save button
+    // Fill the preset picker from the bundled catalog
+    setup_preset_dropdown(&widgets.preset_dropdown);
+    setup_sidebar(&widgets.sidebar_list_box, &widgets.sidebar_sort_dropdown);
+
+    // Generate the profile editor rows from the field schema
+    let gpus = gpu::enumerate_gpus(&gpu::sysfs_root());
+    let field_editors = profile_editor::build_field_editors(
+        &[
+            (profile_fields::FieldGroup::FrameGeneration, widgets.frame_generation_group.clone()),
+            (profile_fields::FieldGroup::Misc, widgets.misc_group.clone()),
+        ],
+        &gpus,
+    );
+
+    // Explain how the default profile is expressed in lsfg-vk's config
+    widgets.default_profile_group.set_description(Some(default_profile::DEFAULT_PROFILE_DESCRIPTION));
+
+    // Create save and save-as-template buttons
+    let save_template_button = gtk::Button::builder()
+        .label("Save as Template…")
+        .action_name("win.save-as-template")
+        .build();
     let save_button = gtk::Button::builder()
         .label("Save Changes")
+        .action_name("win.save")
+        .tooltip_text("Save Changes (Ctrl+S)")
+        .build();
+    let save_button_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
         .halign(gtk::Align::End)
         .margin_end(12)
         .margin_bottom(12)
         .build();
-    widgets.main_settings_box.append(&save_button);
+    save_button_box.append(&save_template_button);
+    save_button_box.append(&save_button);
+    widgets.main_settings_box.append(&save_button_box);
 
     // Initialize application state
     let app_state = Rc::new(RefCell::new(AppState {
         config: initial_config,
+        profile_meta,
         selected_profile_index: None,
         main_window: widgets.main_window.clone(),
+        split_view: widgets.split_view.clone(),
+        content_title: widgets.content_title.clone(),
+        sidebar_width: Cell::new(None),
         sidebar_list_box: widgets.sidebar_list_box.clone(),
-        multiplier_dropdown: widgets.multiplier_dropdown.clone(),
-        flow_scale_entry: widgets.flow_scale_entry.clone(),
-        performance_mode_switch: widgets.performance_mode_switch.clone(),
-        hdr_mode_switch: widgets.hdr_mode_switch.clone(),
-        experimental_present_mode_dropdown: widgets.experimental_present_mode_dropdown.clone(),
+        sidebar_rows: RefCell::new(Vec::new()),
+        sidebar_search_entry: widgets.sidebar_search_entry.clone(),
+        sidebar_sort_dropdown: widgets.sidebar_sort_dropdown.clone(),
+        filter_chip_buttons: widgets.filter_chip_buttons.clone(),
+        bulk_actions_box: widgets.bulk_actions_box.clone(),
+        bulk_selection_label: widgets.bulk_selection_label.clone(),
+        tag_filter_box: widgets.tag_filter_box.clone(),
+        tag_filter_buttons: RefCell::new(Vec::new()),
+        sidebar_group_headers: RefCell::new(HashMap::new()),
+        collapsed_groups: RefCell::new(HashSet::new()),
+        field_editors,
+        preset_dropdown: widgets.preset_dropdown.clone(),
+        preset_status_label: widgets.preset_status_label.clone(),
+        preset_description_label: widgets.preset_description_label.clone(),
+        default_profile_group: widgets.default_profile_group.clone(),
+        add_default_profile_button: widgets.add_default_profile_button.clone(),
+        inherits_dropdown: widgets.inherits_dropdown.clone(),
+        matching_group: widgets.matching_group.clone(),
+        patterns_entry: widgets.patterns_entry.clone(),
+        favorite_switch: widgets.favorite_switch.clone(),
+        group_row: widgets.group_row.clone(),
+        group_entry: widgets.group_entry.clone(),
+        tags_entry: widgets.tags_entry.clone(),
+        notes_view: widgets.notes_view.clone(),
+        match_tester_entry: widgets.match_tester_entry.clone(),
+        match_tester_result_label: widgets.match_tester_result_label.clone(),
+        launch_row: widgets.launch_row.clone(),
+        desktop_launcher_row: widgets.desktop_launcher_row.clone(),
+        gamescope_row: widgets.gamescope_row.clone(),
+        calc_base_fps_spin: widgets.calc_base_fps_spin.clone(),
+        calc_refresh_spin: widgets.calc_refresh_spin.clone(),
+        calc_results_grid: widgets.calc_results_grid.clone(),
+        calc_suggestion_row: widgets.calc_suggestion_row.clone(),
+        calc_apply_button: widgets.calc_apply_button.clone(),
+        gamescope_warning_label: widgets.gamescope_warning_label.clone(),
+        remove_launcher_button: widgets.remove_launcher_button.clone(),
+        steam_app_id_entry: widgets.steam_app_id_entry.clone(),
+        steam_launch_options_row: widgets.steam_launch_options_row.clone(),
+        steam_write_button: widgets.steam_write_button.clone(),
         save_button: save_button.clone(),
         main_settings_box: widgets.main_settings_box.clone(),
-        multiplier_dropdown_handler_id: None,
-        flow_scale_entry_handler_id: None,
-        performance_mode_switch_handler_id: None,
-        hdr_mode_switch_handler_id: None,
-        experimental_present_mode_dropdown_handler_id: None,
+        preset_dropdown_handler_id: None,
+        inherits_dropdown_handler_id: None,
+        patterns_entry_handler_id: None,
+        favorite_switch_handler_id: None,
+        group_entry_handler_id: None,
+        tags_entry_handler_id: None,
+        notes_buffer_handler_id: None,
+        steam_app_id_entry_handler_id: None,
     }));
 
+    // Restore the previous session before any handler can react to it
+    app_state.borrow().restore_ui_state(&saved_ui_state);
+
     // Connect all signal handlers
-    connect_signal_handlers(&widgets, &app_state, &save_button);
+    connect_signal_handlers(&widgets, &app_state);
+    actions::setup_actions(app, &app_state);
+    gamepad_navigation::start_gamepad_navigation(&app_state);
 
     // Initialize UI
-    initialize_ui(&app_state);
+    initialize_ui(&app_state, saved_ui_state);
 
     widgets.main_window.present();
     Ok(())
 }
 
+fn setup_preset_dropdown(dropdown: &gtk::DropDown) {
+    let catalog = presets::preset_catalog();
+    let model = gtk::StringList::new(&["Custom"]);
+    for preset in &catalog.preset {
+        model.append(&preset.name);
+    }
+    dropdown.set_model(Some(&model));
+    dropdown.set_tooltip_text(Some(&format!("Built-in preset catalog v{}", catalog.version)));
+}
+
+fn setup_sidebar(list_box: &gtk::ListBox, sort_dropdown: &gtk::DropDown) {
+    let labels: Vec<&str> = sidebar::SORT_MODES.iter().map(|(_, label)| *label).collect();
+    sort_dropdown.set_model(Some(&gtk::StringList::new(&labels)));
+
+    let placeholder = gtk::Label::builder()
+        .label("No matching profiles")
+        .margin_top(12)
+        .margin_bottom(12)
+        .css_classes(["dim-label"])
+        .build();
+    list_box.set_placeholder(Some(&placeholder));
+}
+
 struct AppWidgets {
     main_window: ApplicationWindow,
-    settings_button: gtk::Button,
+    split_view: libadwaita::NavigationSplitView,
+    content_title: libadwaita::WindowTitle,
+    sidebar_resize_handle: gtk::Box,
     sidebar_list_box: gtk::ListBox,
-    create_profile_button: gtk::Button,
-    multiplier_dropdown: gtk::DropDown,
-    flow_scale_entry: gtk::Entry,
-    performance_mode_switch: gtk::Switch,
-    hdr_mode_switch: gtk::Switch,
-    experimental_present_mode_dropdown: gtk::DropDown,
+    sidebar_search_entry: gtk::SearchEntry,
+    sidebar_sort_dropdown: gtk::DropDown,
+    filter_chip_buttons: Vec<(sidebar::FilterChip, gtk::ToggleButton)>,
+    tag_filter_box: gtk::FlowBox,
+    bulk_actions_box: gtk::Box,
+    bulk_selection_label: gtk::Label,
+    frame_generation_group: libadwaita::PreferencesGroup,
+    misc_group: libadwaita::PreferencesGroup,
+    preset_dropdown: gtk::DropDown,
+    preset_status_label: gtk::Label,
+    preset_description_label: gtk::Label,
+    default_profile_group: libadwaita::PreferencesGroup,
+    add_default_profile_button: gtk::Button,
+    inherits_dropdown: gtk::DropDown,
+    matching_group: libadwaita::PreferencesGroup,
+    patterns_entry: gtk::Entry,
+    expand_patterns_button: gtk::Button,
+    favorite_switch: gtk::Switch,
+    group_row: libadwaita::ActionRow,
+    group_entry: gtk::Entry,
+    tags_entry: gtk::Entry,
+    notes_view: gtk::TextView,
+    match_tester_entry: gtk::Entry,
+    match_tester_pick_button: gtk::Button,
+    match_tester_result_label: gtk::Label,
+    launch_row: libadwaita::ActionRow,
+    launch_settings_button: gtk::Button,
+    desktop_launcher_row: libadwaita::ActionRow,
+    gamescope_row: libadwaita::ActionRow,
+    calc_base_fps_spin: gtk::SpinButton,
+    calc_refresh_spin: gtk::SpinButton,
+    calc_detect_refresh_button: gtk::Button,
+    calc_results_grid: gtk::Grid,
+    calc_suggestion_row: libadwaita::ActionRow,
+    calc_apply_button: gtk::Button,
+    gamescope_warning_label: gtk::Label,
+    gamescope_settings_button: gtk::Button,
+    create_launcher_button: gtk::Button,
+    remove_launcher_button: gtk::Button,
+    list_launchers_button: gtk::Button,
+    steam_app_id_entry: gtk::Entry,
+    steam_launch_options_row: libadwaita::ActionRow,
+    steam_copy_button: gtk::Button,
+    steam_write_button: gtk::Button,
     main_settings_box: gtk::Box,
 }
 
 fn extract_widgets_from_builder(builder: &Builder) -> Result<AppWidgets, String> {
     Ok(AppWidgets {
         main_window: WidgetUtils::get_widget_from_builder(builder, "main_window")?,
-        settings_button: WidgetUtils::get_widget_from_builder(builder, "settings_button")?,
+        split_view: WidgetUtils::get_widget_from_builder(builder, "split_view")?,
+        content_title: WidgetUtils::get_widget_from_builder(builder, "content_title")?,
+        sidebar_resize_handle: WidgetUtils::get_widget_from_builder(builder, "sidebar_resize_handle")?,
         sidebar_list_box: WidgetUtils::get_widget_from_builder(builder, "sidebar_list_box")?,
-        create_profile_button: WidgetUtils::get_widget_from_builder(builder, "create_profile_button")?,
-        multiplier_dropdown: WidgetUtils::get_widget_from_builder(builder, "multiplier_dropdown")?,
-        flow_scale_entry: WidgetUtils::get_widget_from_builder(builder, "flow_scale_entry")?,
-        performance_mode_switch: WidgetUtils::get_widget_from_builder(builder, "performance_mode_switch")?,
-        hdr_mode_switch: WidgetUtils::get_widget_from_builder(builder, "hdr_mode_switch")?,
-        experimental_present_mode_dropdown: WidgetUtils::get_widget_from_builder(builder, "experimental_present_mode_dropdown")?,
+        sidebar_search_entry: WidgetUtils::get_widget_from_builder(builder, "sidebar_search_entry")?,
+        sidebar_sort_dropdown: WidgetUtils::get_widget_from_builder(builder, "sidebar_sort_dropdown")?,
+        filter_chip_buttons: sidebar::FilterChip::ALL.into_iter()
+            .map(|chip| {
+                WidgetUtils::get_widget_from_builder(builder, &format!("filter_{}_button", chip.key()))
+                    .map(|button| (chip, button))
+            })
+            .collect::<Result<_, _>>()?,
+        tag_filter_box: WidgetUtils::get_widget_from_builder(builder, "tag_filter_box")?,
+        bulk_actions_box: WidgetUtils::get_widget_from_builder(builder, "bulk_actions_box")?,
+        bulk_selection_label: WidgetUtils::get_widget_from_builder(builder, "bulk_selection_label")?,
+        frame_generation_group: WidgetUtils::get_widget_from_builder(builder, "frame_generation_group")?,
+        misc_group: WidgetUtils::get_widget_from_builder(builder, "misc_group")?,
+        preset_dropdown: WidgetUtils::get_widget_from_builder(builder, "preset_dropdown")?,
+        preset_status_label: WidgetUtils::get_widget_from_builder(builder, "preset_status_label")?,
+        preset_description_label: WidgetUtils::get_widget_from_builder(builder, "preset_description_label")?,
+        default_profile_group: WidgetUtils::get_widget_from_builder(builder, "default_profile_group")?,
+        add_default_profile_button: WidgetUtils::get_widget_from_builder(builder, "add_default_profile_button")?,
+        inherits_dropdown: WidgetUtils::get_widget_from_builder(builder, "inherits_dropdown")?,
+        matching_group: WidgetUtils::get_widget_from_builder(builder, "matching_group")?,
+        patterns_entry: WidgetUtils::get_widget_from_builder(builder, "patterns_entry")?,
+        expand_patterns_button: WidgetUtils::get_widget_from_builder(builder, "expand_patterns_button")?,
+        favorite_switch: WidgetUtils::get_widget_from_builder(builder, "favorite_switch")?,
+        group_row: WidgetUtils::get_widget_from_builder(builder, "group_row")?,
+        group_entry: WidgetUtils::get_widget_from_builder(builder, "group_entry")?,
+        tags_entry: WidgetUtils::get_widget_from_builder(builder, "tags_entry")?,
+        notes_view: WidgetUtils::get_widget_from_builder(builder, "notes_view")?,
+        match_tester_entry: WidgetUtils::get_widget_from_builder(builder, "match_tester_entry")?,
+        match_tester_pick_button: WidgetUtils::get_widget_from_builder(builder, "match_tester_pick_button")?,
+        match_tester_result_label: WidgetUtils::get_widget_from_builder(builder, "match_tester_result_label")?,
+        launch_row: WidgetUtils::get_widget_from_builder(builder, "launch_row")?,
+        launch_settings_button: WidgetUtils::get_widget_from_builder(builder, "launch_settings_button")?,
+        desktop_launcher_row: WidgetUtils::get_widget_from_builder(builder, "desktop_launcher_row")?,
+        gamescope_row: WidgetUtils::get_widget_from_builder(builder, "gamescope_row")?,
+        calc_base_fps_spin: WidgetUtils::get_widget_from_builder(builder, "calc_base_fps_spin")?,
+        calc_refresh_spin: WidgetUtils::get_widget_from_builder(builder, "calc_refresh_spin")?,
+        calc_detect_refresh_button: WidgetUtils::get_widget_from_builder(builder, "calc_detect_refresh_button")?,
+        calc_results_grid: WidgetUtils::get_widget_from_builder(builder, "calc_results_grid")?,
+        calc_suggestion_row: WidgetUtils::get_widget_from_builder(builder, "calc_suggestion_row")?,
+        calc_apply_button: WidgetUtils::get_widget_from_builder(builder, "calc_apply_button")?,
+        gamescope_warning_label: WidgetUtils::get_widget_from_builder(builder, "gamescope_warning_label")?,
+        gamescope_settings_button: WidgetUtils::get_widget_from_builder(builder, "gamescope_settings_button")?,
+        create_launcher_button: WidgetUtils::get_widget_from_builder(builder, "create_launcher_button")?,
+        remove_launcher_button: WidgetUtils::get_widget_from_builder(builder, "remove_launcher_button")?,
+        list_launchers_button: WidgetUtils::get_widget_from_builder(builder, "list_launchers_button")?,
+        steam_app_id_entry: WidgetUtils::get_widget_from_builder(builder, "steam_app_id_entry")?,
+        steam_launch_options_row: WidgetUtils::get_widget_from_builder(builder, "steam_launch_options_row")?,
+        steam_copy_button: WidgetUtils::get_widget_from_builder(builder, "steam_copy_button")?,
+        steam_write_button: WidgetUtils::get_widget_from_builder(builder, "steam_write_button")?,
         main_settings_box: WidgetUtils::get_widget_from_builder(builder, "main_box")?,
     })
 }
 
-fn connect_signal_handlers(
-    widgets: &AppWidgets,
-    app_state: &Rc<RefCell<AppState>>,
-    save_button: &gtk::Button,
-) {
-    // Connect settings button
-    let main_window_clone = widgets.main_window.clone();
-    let app_state_clone = app_state.clone();
-    widgets.settings_button.connect_clicked(move |_| {
-        let settings_win = settings_window::create_settings_window(&main_window_clone, app_state_clone.clone());
-        settings_win.present();
-    });
-
+fn connect_signal_handlers(widgets: &AppWidgets, app_state: &Rc<RefCell<AppState>>) {
     // Connect profile-related signal handlers
     connect_profile_signal_handlers(app_state);
-    connect_save_button_handler(save_button, app_state);
+    connect_expand_patterns_button_handler(&widgets.expand_patterns_button, app_state);
+    connect_match_tester_handlers(&widgets.match_tester_entry, &widgets.match_tester_pick_button, app_state);
+    connect_launch_button_handler(&widgets.launch_settings_button, app_state);
+    connect_gamescope_button_handler(&widgets.gamescope_settings_button, app_state);
+    connect_frame_calculator_handlers(&widgets.calc_detect_refresh_button, app_state);
+    connect_steam_button_handlers(&widgets.steam_copy_button, &widgets.steam_write_button, app_state);
+    connect_launcher_button_handlers(
+        &widgets.create_launcher_button,
+        &widgets.remove_launcher_button,
+        &widgets.list_launchers_button,
+        app_state,
+    );
+    connect_sidebar_selection_handler(&widgets.sidebar_list_box, app_state);
     connect_sidebar_row_activated_handler(&widgets.sidebar_list_box, app_state);
-    connect_create_profile_button_handler(&widgets.create_profile_button, app_state);
+    connect_sidebar_reorder_handler(&widgets.sidebar_list_box, app_state);
+    connect_sidebar_resize_handler(&widgets.sidebar_resize_handle, app_state);
+    connect_adaptive_layout_handler(&widgets.split_view, &widgets.sidebar_resize_handle, &widgets.main_window);
+    connect_main_window_close_handler(&widgets.main_window, app_state);
+    connect_sidebar_search_handlers(app_state);
 }
 
-fn initialize_ui(app_state: &Rc<RefCell<AppState>>) {
+fn initialize_ui(app_state: &Rc<RefCell<AppState>>, saved_ui_state: ui_state::UiState) {
     let app_state_clone = app_state.clone();
     glib::idle_add_local(move || {
         let mut state = app_state_clone.borrow_mut();
+        // Select the profile selected last time, or the first one
+        let saved_selection = saved_ui_state.selected_profile.as_ref()
+            .and_then(|exe| state.config.game.iter().position(|profile| profile.exe == *exe));
         if state.config.game.first().is_some() {
-            state.selected_profile_index = Some(0);
+            state.selected_profile_index = Some(saved_selection.unwrap_or(0));
         }
         state.populate_sidebar_with_handlers(Some(app_state_clone.clone()));
+        let tag_buttons = state.tag_filter_buttons.borrow().clone();
         drop(state);
+
+        // Tag chips only exist once the sidebar is populated
+        for (tag, button) in tag_buttons {
+            if saved_ui_state.filter_tags.contains(&tag) {
+                button.set_active(true);
+            }
+        }
         
         if app_state_clone.borrow().selected_profile_index.is_some() {
             app_state_clone.borrow().update_main_window_from_profile();
@@ -0,0 +1,76 @@
+//! Built-in tuning presets
+//! The catalog is bundled from `resources/presets.toml` and maps each preset
+//! onto a subset of `GameProfile` fields.
+
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::config::GameProfile;
+use crate::utils::round_to_2_decimals;
+
+#[derive(Debug, Deserialize)]
+pub struct PresetCatalog {
+    pub version: u32,
+    #[serde(default)]
+    pub preset: Vec<TuningPreset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TuningPreset {
+    pub name: String,
+    pub description: String,
+    // Fields left as None are not touched by the preset
+    pub multiplier: Option<u32>,
+    pub flow_scale: Option<f32>,
+    pub performance_mode: Option<bool>,
+    pub hdr_mode: Option<bool>,
+    pub experimental_present_mode: Option<String>,
+}
+
+impl TuningPreset {
+    /// Writes the preset's values into a profile, leaving unspecified fields alone
+    pub fn apply(&self, profile: &mut GameProfile) {
+        if let Some(multiplier) = self.multiplier {
+            profile.multiplier = multiplier;
+        }
+        if let Some(flow_scale) = self.flow_scale {
+            profile.flow_scale = round_to_2_decimals(flow_scale);
+        }
+        if let Some(performance_mode) = self.performance_mode {
+            profile.performance_mode = performance_mode;
+        }
+        if let Some(hdr_mode) = self.hdr_mode {
+            profile.hdr_mode = hdr_mode;
+        }
+        if let Some(mode) = &self.experimental_present_mode {
+            profile.experimental_present_mode = mode.clone();
+        }
+    }
+
+    /// Returns true if every field the preset specifies still has the preset's value
+    pub fn matches(&self, profile: &GameProfile) -> bool {
+        self.multiplier.is_none_or(|m| profile.multiplier == m)
+            && self.flow_scale.is_none_or(|f| {
+                (round_to_2_decimals(profile.flow_scale) - round_to_2_decimals(f)).abs() < 0.001
+            })
+            && self.performance_mode.is_none_or(|p| profile.performance_mode == p)
+            && self.hdr_mode.is_none_or(|h| profile.hdr_mode == h)
+            && self.experimental_present_mode.as_ref().is_none_or(|m| profile.experimental_present_mode == *m)
+    }
+}
+
+/// Returns the bundled preset catalog, parsed once on first use
+pub fn preset_catalog() -> &'static PresetCatalog {
+    static CATALOG: OnceLock<PresetCatalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        toml::from_str(include_str!("../resources/presets.toml")).unwrap_or_else(|e| {
+            eprintln!("Failed to parse bundled presets: {}", e);
+            PresetCatalog { version: 0, preset: Vec::new() }
+        })
+    })
+}
+
+/// Finds the index of the first preset the profile still matches
+pub fn find_matching_preset(profile: &GameProfile) -> Option<usize> {
+    preset_catalog().preset.iter().position(|preset| preset.matches(profile))
+}
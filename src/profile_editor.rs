@@ -0,0 +1,308 @@
+//! Profile editor generated from `profile_fields::PROFILE_FIELDS`
+//! Each field gets a row with its editing widget, a "reset to inherited"
+//! button and a badge for values the widget has no entry for. Showing,
+//! reading back and signal blocking all go through `FieldEditor`, so none of
+//! them needs to know which fields exist.
+
+use gtk::prelude::*;
+use gtk::glib;
+use libadwaita::prelude::*;
+use libadwaita::{ActionRow, PreferencesGroup};
+use std::rc::Rc;
+
+use crate::capabilities::{multiplier_label, MULTIPLIER_CHOICES, MULTIPLIER_TOOLTIP};
+use crate::gpu::GpuDevice;
+use crate::profile_fields::{ChoiceSource, FieldDescriptor, FieldGroup, FieldKind, FieldValue, ProfileField, PROFILE_FIELDS};
+use crate::ui_components::{ButtonFactory, PreferencesFactory};
+
+/// Prefix of the dropdown entry that shows a value the editor has no item for
+pub const CUSTOM_VALUE_PREFIX: &str = "custom: ";
+
+/// Dropdown label of the entry that asks for an arbitrary value
+pub const CUSTOM_ENTRY_LABEL: &str = "Custom…";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChoiceItem {
+    pub value: Option<String>,
+    pub label: String,
+}
+
+#[derive(Clone)]
+pub enum FieldWidget {
+    Toggle(gtk::Switch),
+    Number(gtk::SpinButton),
+    /// Known `items` come first, followed by an optional "custom: …" entry
+    /// and the "Custom…" entry if the field has one
+    Choice { dropdown: gtk::DropDown, items: Rc<Vec<ChoiceItem>> },
+}
+
+/// What the user picked in an editor widget
+pub enum EditorInput {
+    Value(FieldValue),
+    /// "Custom…" was selected; the value still has to be asked for
+    CustomRequested,
+}
+
+impl FieldWidget {
+    pub fn widget(&self) -> gtk::Widget {
+        match self {
+            FieldWidget::Toggle(switch) => switch.clone().upcast(),
+            FieldWidget::Number(spin) => spin.clone().upcast(),
+            FieldWidget::Choice { dropdown, .. } => dropdown.clone().upcast(),
+        }
+    }
+
+    /// The object whose change signal `connect_changed` connects to
+    pub fn object(&self) -> &glib::Object {
+        match self {
+            FieldWidget::Toggle(switch) => switch.upcast_ref(),
+            FieldWidget::Number(spin) => spin.upcast_ref(),
+            FieldWidget::Choice { dropdown, .. } => dropdown.upcast_ref(),
+        }
+    }
+
+    pub fn read(&self) -> Option<EditorInput> {
+        match self {
+            FieldWidget::Toggle(switch) => Some(EditorInput::Value(FieldValue::Toggle(switch.is_active()))),
+            FieldWidget::Number(spin) => Some(EditorInput::Value(FieldValue::Number(spin.value()))),
+            FieldWidget::Choice { dropdown, items } => {
+                let position = dropdown.selected() as usize;
+                if let Some(item) = items.get(position) {
+                    return Some(EditorInput::Value(FieldValue::Choice(item.value.clone())));
+                }
+                let label = dropdown.model()
+                    .and_downcast::<gtk::StringList>()
+                    .and_then(|model| model.string(position as u32))?;
+                match label.strip_prefix(CUSTOM_VALUE_PREFIX) {
+                    Some(value) => Some(EditorInput::Value(FieldValue::Choice(Some(value.to_string())))),
+                    None if label == CUSTOM_ENTRY_LABEL => Some(EditorInput::CustomRequested),
+                    None => None,
+                }
+            }
+        }
+    }
+
+    /// Calls `on_change` with the new input whenever the user edits the widget
+    pub fn connect_changed<F: Fn(EditorInput) + 'static>(&self, on_change: F) -> glib::SignalHandlerId {
+        let widget = self.clone();
+        let notify = move || {
+            if let Some(input) = widget.read() {
+                on_change(input);
+            }
+        };
+        match self {
+            FieldWidget::Toggle(switch) => switch.connect_active_notify(move |_| notify()),
+            FieldWidget::Number(spin) => spin.connect_value_changed(move |_| notify()),
+            FieldWidget::Choice { dropdown, .. } => dropdown.connect_selected_notify(move |_| notify()),
+        }
+    }
+}
+
+pub struct FieldEditor {
+    pub field: ProfileField,
+    pub row: ActionRow,
+    pub widget: FieldWidget,
+    pub reset_button: gtk::Button,
+    pub custom_badge: gtk::Image,
+    pub handler_id: Option<glib::SignalHandlerId>,
+}
+
+impl FieldEditor {
+    /// Shows a value; values the widget cannot represent are kept as a custom
+    /// entry (or a widened range) and flagged with the badge
+    pub fn show_value(&self, value: &FieldValue) {
+        let custom = match (&self.widget, value) {
+            (FieldWidget::Toggle(switch), FieldValue::Toggle(active)) => {
+                switch.set_active(*active);
+                false
+            }
+            (FieldWidget::Number(spin), FieldValue::Number(number)) => {
+                let FieldKind::Number { min, max, .. } = &self.field.descriptor().kind else {
+                    return;
+                };
+                let outside = number < min || number > max;
+                // Widening the range keeps the spin button from clamping the value
+                spin.set_range(min.min(*number), max.max(*number));
+                spin.set_value(*number);
+                outside
+            }
+            (FieldWidget::Choice { dropdown, items }, FieldValue::Choice(choice)) => {
+                select_or_preserve_choice(dropdown, items, choice.as_deref())
+            }
+            _ => {
+                eprintln!("Cannot show {:?} in the {} editor", value, self.field.key());
+                false
+            }
+        };
+        self.set_custom_badge(custom.then(|| value.describe()));
+    }
+
+    // Flags a value the editor cannot represent; it is kept as is unless changed
+    fn set_custom_badge(&self, custom_value: Option<String>) {
+        self.custom_badge.set_visible(custom_value.is_some());
+        if let Some(value) = custom_value {
+            self.custom_badge.set_tooltip_text(Some(&format!(
+                "'{}' is not a value this editor knows. It is kept as is unless you pick another one.",
+                value
+            )));
+        }
+    }
+
+    /// Shows the default value with no badge, for when no profile is selected
+    pub fn clear(&self) {
+        self.show_value(&self.field.descriptor().default_value());
+    }
+
+    /// Marks a field the profiles being bulk edited disagree on. Dropdowns show
+    /// no selection; switches and spin buttons show `sample` dimmed, as they
+    /// have no indeterminate state.
+    pub fn show_mixed(&self, sample: &FieldValue) {
+        match &self.widget {
+            FieldWidget::Choice { dropdown, .. } => dropdown.set_selected(gtk::INVALID_LIST_POSITION),
+            _ => self.show_value(sample),
+        }
+        self.widget.widget().add_css_class("mixed-value");
+        self.row.set_subtitle("Mixed values; pick one to set it on every selected profile");
+    }
+
+    pub fn clear_mixed(&self) {
+        self.widget.widget().remove_css_class("mixed-value");
+        self.row.set_subtitle(self.field.descriptor().description);
+    }
+
+    /// Dims inherited values; the reset button is offered for overrides only
+    pub fn set_inherited(&self, inherited: bool, has_base: bool) {
+        let widget = self.widget.widget();
+        if inherited {
+            widget.add_css_class("inherited-value");
+        } else {
+            widget.remove_css_class("inherited-value");
+        }
+        self.reset_button.set_visible(has_base && !inherited);
+    }
+}
+
+/// Selects `value`, or shows it as a "custom: …" entry after the known items
+/// instead of leaving the previous selection in place. The entry is dropped
+/// again once a known value is selected. Returns true if it was needed.
+fn select_or_preserve_choice(dropdown: &gtk::DropDown, items: &[ChoiceItem], value: Option<&str>) -> bool {
+    let Some(model) = dropdown.model().and_downcast::<gtk::StringList>() else {
+        return false;
+    };
+    // Drops a custom entry left over from another profile
+    let custom_position = items.len() as u32;
+    if model.string(custom_position).is_some_and(|label| label.starts_with(CUSTOM_VALUE_PREFIX)) {
+        model.remove(custom_position);
+    }
+
+    if let Some(position) = items.iter().position(|item| item.value.as_deref() == value) {
+        dropdown.set_selected(position as u32);
+        return false;
+    }
+    let label = format!("{}{}", CUSTOM_VALUE_PREFIX, value.unwrap_or_default());
+    model.splice(custom_position, 0, &[label.as_str()]);
+    dropdown.set_selected(custom_position);
+    true
+}
+
+/// Resolves a choice source into dropdown items, plus a tooltip for the dropdown
+fn choice_items(source: &ChoiceSource, gpus: &[GpuDevice]) -> (Vec<ChoiceItem>, Option<String>) {
+    match source {
+        ChoiceSource::Fixed(choices) => {
+            let items = choices.iter()
+                .map(|(value, label)| ChoiceItem { value: Some(value.to_string()), label: label.to_string() })
+                .collect();
+            (items, None)
+        }
+        ChoiceSource::Multipliers => {
+            let items = MULTIPLIER_CHOICES.iter()
+                .map(|multiplier| ChoiceItem { value: Some(multiplier.to_string()), label: multiplier_label(*multiplier) })
+                .collect();
+            (items, Some(MULTIPLIER_TOOLTIP.to_string()))
+        }
+        ChoiceSource::Gpus => {
+            let automatic = ChoiceItem { value: None, label: "Automatic".to_string() };
+            let items = std::iter::once(automatic)
+                .chain(gpus.iter().map(|device| ChoiceItem { value: Some(device.selection_key()), label: device.label() }))
+                .collect();
+            (items, None)
+        }
+    }
+}
+
+fn build_widget(descriptor: &FieldDescriptor, gpus: &[GpuDevice]) -> FieldWidget {
+    match &descriptor.kind {
+        FieldKind::Toggle => FieldWidget::Toggle(
+            gtk::Switch::builder()
+                .valign(gtk::Align::Center)
+                .build(),
+        ),
+        FieldKind::Number { min, max, step, digits } => {
+            let spin = gtk::SpinButton::with_range(*min, *max, *step);
+            spin.set_digits(*digits);
+            spin.set_valign(gtk::Align::Center);
+            FieldWidget::Number(spin)
+        }
+        FieldKind::Choice { source, custom_range } => {
+            let (items, tooltip) = choice_items(source, gpus);
+            let model = gtk::StringList::new(&[]);
+            for item in &items {
+                model.append(&item.label);
+            }
+            if custom_range.is_some() {
+                model.append(CUSTOM_ENTRY_LABEL);
+            }
+            let dropdown = gtk::DropDown::builder()
+                .model(&model)
+                .valign(gtk::Align::Center)
+                .build();
+            dropdown.set_tooltip_text(tooltip.as_deref());
+            FieldWidget::Choice { dropdown, items: Rc::new(items) }
+        }
+    }
+}
+
+/// Builds one row per entry of `PROFILE_FIELDS` into the group it belongs to
+pub fn build_field_editors(
+    groups: &[(FieldGroup, PreferencesGroup)],
+    gpus: &[GpuDevice],
+) -> Vec<FieldEditor> {
+    PROFILE_FIELDS.iter()
+        .filter_map(|descriptor| {
+            let Some((_, group)) = groups.iter().find(|(group, _)| *group == descriptor.group) else {
+                eprintln!("No editor group for {}", descriptor.key);
+                return None;
+            };
+            let row = PreferencesFactory::create_action_row(descriptor.title, Some(descriptor.description));
+            row.set_tooltip_text(Some(&format!("[[game]] {}", descriptor.key)));
+
+            let custom_badge = gtk::Image::builder()
+                .icon_name("dialog-warning-symbolic")
+                .valign(gtk::Align::Center)
+                .visible(false)
+                .css_classes(["warning"])
+                .build();
+            let reset_button = ButtonFactory::create_circular_icon_button("edit-undo-symbolic", "Reset to inherited value");
+            reset_button.set_valign(gtk::Align::Center);
+            reset_button.set_visible(false);
+
+            let widget = build_widget(descriptor, gpus);
+            row.add_suffix(&custom_badge);
+            row.add_suffix(&reset_button);
+            row.add_suffix(&widget.widget());
+            if let FieldWidget::Toggle(switch) = &widget {
+                row.set_activatable_widget(Some(switch));
+            }
+            group.add(&row);
+
+            Some(FieldEditor {
+                field: descriptor.field,
+                row,
+                widget,
+                reset_button,
+                custom_badge,
+                handler_id: None,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,240 @@
+//! Field-level access to `GameProfile`
+//! Every editable setting is described once in `PROFILE_FIELDS`: its conf.toml
+//! key, how the editor shows it and how values are read and written. The
+//! profile editor, inheritance and comparisons are all driven by this table,
+//! so supporting a new lsfg-vk option takes a field on `GameProfile`, a
+//! `ProfileField` variant and one entry here.
+
+use std::str::FromStr;
+
+use crate::config::GameProfile;
+use crate::utils::round_to_2_decimals;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfileField {
+    Multiplier,
+    FlowScale,
+    PerformanceMode,
+    HdrMode,
+    ExperimentalPresentMode,
+    Gpu,
+}
+
+/// A field value as the editor sees it
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Toggle(bool),
+    Number(f64),
+    /// `None` is the "not set" choice, e.g. letting lsfg-vk pick the GPU
+    Choice(Option<String>),
+}
+
+impl FieldValue {
+    /// Short text for tooltips and summaries
+    pub fn describe(&self) -> String {
+        match self {
+            FieldValue::Toggle(true) => "on".to_string(),
+            FieldValue::Toggle(false) => "off".to_string(),
+            FieldValue::Number(value) => format!("{:.2}", value),
+            FieldValue::Choice(Some(value)) => value.clone(),
+            FieldValue::Choice(None) => "not set".to_string(),
+        }
+    }
+}
+
+/// Preferences group of the main window a field is shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldGroup {
+    FrameGeneration,
+    Misc,
+}
+
+pub enum ChoiceSource {
+    /// Fixed `(value, label)` pairs
+    Fixed(&'static [(&'static str, &'static str)]),
+    /// `capabilities::MULTIPLIER_CHOICES`
+    Multipliers,
+    /// "Automatic" followed by the GPUs found on this machine
+    Gpus,
+}
+
+pub enum FieldKind {
+    Toggle,
+    Number { min: f64, max: f64, step: f64, digits: u32 },
+    /// A dropdown; `custom_range` adds a "Custom…" entry that asks for any
+    /// whole number in that range
+    Choice { source: ChoiceSource, custom_range: Option<(u32, u32)> },
+}
+
+pub struct FieldDescriptor {
+    pub field: ProfileField,
+    /// Key in a conf.toml `[[game]]` entry
+    pub key: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub group: FieldGroup,
+    pub kind: FieldKind,
+    pub get: fn(&GameProfile) -> FieldValue,
+    /// Fails for values of the wrong kind or that do not parse
+    pub set: fn(&mut GameProfile, FieldValue) -> Result<(), String>,
+}
+
+fn toggle_value(value: FieldValue) -> Result<bool, String> {
+    match value {
+        FieldValue::Toggle(value) => Ok(value),
+        other => Err(format!("Expected on or off, got {:?}", other)),
+    }
+}
+
+fn number_value(value: FieldValue) -> Result<f64, String> {
+    match value {
+        FieldValue::Number(value) => Ok(value),
+        other => Err(format!("Expected a number, got {:?}", other)),
+    }
+}
+
+fn choice_value(value: FieldValue) -> Result<Option<String>, String> {
+    match value {
+        FieldValue::Choice(value) => Ok(value),
+        other => Err(format!("Expected a choice, got {:?}", other)),
+    }
+}
+
+/// Parses a choice that must be set, such as a multiplier
+fn parse_choice<T: FromStr>(value: FieldValue) -> Result<T, String> {
+    let text = choice_value(value)?.ok_or("A value is required")?;
+    text.parse().map_err(|_| format!("'{}' is not a valid value", text))
+}
+
+pub const PROFILE_FIELDS: &[FieldDescriptor] = &[
+    FieldDescriptor {
+        field: ProfileField::Multiplier,
+        key: "multiplier",
+        title: "Multiplier",
+        description: "Frames shown per rendered frame; off disables frame generation",
+        group: FieldGroup::FrameGeneration,
+        kind: FieldKind::Choice { source: ChoiceSource::Multipliers, custom_range: Some((1, 100)) },
+        get: |profile| FieldValue::Choice(Some(profile.multiplier.to_string())),
+        set: |profile, value| {
+            profile.multiplier = parse_choice(value)?;
+            Ok(())
+        },
+    },
+    FieldDescriptor {
+        field: ProfileField::FlowScale,
+        key: "flow_scale",
+        title: "Flow Scale",
+        description: "Resolution of motion estimation; lower is faster but less accurate",
+        group: FieldGroup::FrameGeneration,
+        kind: FieldKind::Number { min: 0.25, max: 1.0, step: 0.05, digits: 2 },
+        get: |profile| FieldValue::Number(round_to_2_decimals(profile.flow_scale) as f64),
+        set: |profile, value| {
+            profile.flow_scale = round_to_2_decimals(number_value(value)? as f32);
+            Ok(())
+        },
+    },
+    FieldDescriptor {
+        field: ProfileField::PerformanceMode,
+        key: "performance_mode",
+        title: "Performance Mode",
+        description: "Use a lighter frame generation model",
+        group: FieldGroup::FrameGeneration,
+        kind: FieldKind::Toggle,
+        get: |profile| FieldValue::Toggle(profile.performance_mode),
+        set: |profile, value| {
+            profile.performance_mode = toggle_value(value)?;
+            Ok(())
+        },
+    },
+    FieldDescriptor {
+        field: ProfileField::HdrMode,
+        key: "hdr_mode",
+        title: "HDR Mode",
+        description: "Enable for games that output HDR",
+        group: FieldGroup::Misc,
+        kind: FieldKind::Toggle,
+        get: |profile| FieldValue::Toggle(profile.hdr_mode),
+        set: |profile, value| {
+            profile.hdr_mode = toggle_value(value)?;
+            Ok(())
+        },
+    },
+    FieldDescriptor {
+        field: ProfileField::ExperimentalPresentMode,
+        key: "experimental_present_mode",
+        title: "Experimental Present Mode",
+        description: "How generated frames are paced to the display",
+        group: FieldGroup::Misc,
+        kind: FieldKind::Choice {
+            source: ChoiceSource::Fixed(&[("vsync", "vsync"), ("mailbox", "mailbox"), ("immediate", "immediate")]),
+            custom_range: None,
+        },
+        get: |profile| FieldValue::Choice(Some(profile.experimental_present_mode.clone())),
+        set: |profile, value| {
+            profile.experimental_present_mode = choice_value(value)?.ok_or("A present mode is required")?;
+            Ok(())
+        },
+    },
+    FieldDescriptor {
+        field: ProfileField::Gpu,
+        key: "gpu",
+        title: "GPU",
+        description: "GPU that runs frame generation",
+        group: FieldGroup::Misc,
+        kind: FieldKind::Choice { source: ChoiceSource::Gpus, custom_range: None },
+        get: |profile| FieldValue::Choice(profile.gpu.clone()),
+        set: |profile, value| {
+            profile.gpu = choice_value(value)?;
+            Ok(())
+        },
+    },
+];
+
+impl FieldDescriptor {
+    /// The value a new profile starts with
+    pub fn default_value(&self) -> FieldValue {
+        (self.get)(&GameProfile::default())
+    }
+}
+
+impl ProfileField {
+    /// Every editable setting, in editor order (the executable name is not a setting)
+    pub fn all() -> impl Iterator<Item = ProfileField> {
+        PROFILE_FIELDS.iter().map(|descriptor| descriptor.field)
+    }
+
+    pub fn descriptor(self) -> &'static FieldDescriptor {
+        PROFILE_FIELDS.iter()
+            .find(|descriptor| descriptor.field == self)
+            .expect("every profile field has an entry in PROFILE_FIELDS")
+    }
+
+    /// Key used in conf.toml
+    pub fn key(self) -> &'static str {
+        self.descriptor().key
+    }
+
+    pub fn value(self, profile: &GameProfile) -> FieldValue {
+        (self.descriptor().get)(profile)
+    }
+
+    pub fn set_value(self, profile: &mut GameProfile, value: FieldValue) -> Result<(), String> {
+        (self.descriptor().set)(profile, value)
+    }
+
+    /// Copies this field's value from one profile to another
+    pub fn copy_value(self, from: &GameProfile, to: &mut GameProfile) {
+        if let Err(e) = self.set_value(to, self.value(from)) {
+            eprintln!("Failed to copy {}: {}", self.key(), e);
+        }
+    }
+
+    pub fn values_equal(self, a: &GameProfile, b: &GameProfile) -> bool {
+        self.value(a) == self.value(b)
+    }
+}
+
+/// Lists the fields whose values differ between two profiles
+pub fn changed_fields(before: &GameProfile, after: &GameProfile) -> Vec<ProfileField> {
+    ProfileField::all().filter(|field| !field.values_equal(before, after)).collect()
+}
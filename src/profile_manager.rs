@@ -1,13 +1,18 @@
 use gtk::prelude::*;
-use gtk::{glib, Entry};
+use gtk::{glib, DropDown, Entry};
 use libadwaita::ApplicationWindow;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::desktop_launcher::remove_launcher;
 use crate::config::{Config, GameProfile};
 use crate::app_state::AppState;
-use crate::ui_components::{DialogFactory, ProcessPickerFactory};
-use crate::utils::get_vulkan_processes;
+use crate::ui_components::{DialogFactory, FeedbackUtils, LayoutFactory, ProcessPickerFactory};
+use crate::sidebar::{profile_group, SortMode};
+use crate::templates::{list_profile_sources, load_templates, save_profile_as_template, ProfileSource, TemplateFile};
+use crate::utils::{get_vulkan_processes, VulkanProcess};
+use crate::default_profile::is_default_profile;
+use crate::profile_fields::{FieldKind, FieldValue, ProfileField};
 
 /// Validates if a profile name is unique and not empty
 pub fn validate_profile_name(config: &Config, name: &str, exclude_index: Option<usize>) -> Result<(), String> {
@@ -16,6 +21,13 @@ pub fn validate_profile_name(config: &Config, name: &str, exclude_index: Option<
     }
     
     let name = name.trim();
+    if name.contains(char::is_control) {
+        return Err("Profile names cannot contain line breaks or other control characters".to_string());
+    }
+    if name.contains(['*', '?']) {
+        return Err("Profile names are matched literally; add wildcards under Executable Matching instead".to_string());
+    }
+
     let exists = config.game.iter().enumerate().any(|(idx, profile)| {
         profile.exe == name && exclude_index.map_or(true, |exclude_idx| idx != exclude_idx)
     });
@@ -43,28 +55,74 @@ pub fn show_profile_error<W: gtk::prelude::IsA<gtk::Window>>(parent: &W, error_m
 
 /// Creates a new profile dialog and handles the creation process
 pub fn show_create_profile_dialog(app_state: Rc<RefCell<AppState>>) {
+    show_create_profile_dialog_with_source(app_state, "New Profile", None, ProfileSource::Defaults);
+}
+
+/// Shows the create dialog pre-filled to copy the settings of an existing profile
+pub fn show_duplicate_profile_dialog(app_state: Rc<RefCell<AppState>>, profile_index: usize) {
+    let suggested_name = {
+        let state = app_state.borrow();
+        unique_profile_name(&state.config, &state.config.game[profile_index].exe)
+    };
+    show_create_profile_dialog_with_source(
+        app_state,
+        "Duplicate Profile",
+        Some(&suggested_name),
+        ProfileSource::Profile(profile_index),
+    );
+}
+
+/// Builds the create dialog with a "Based on" dropdown listing defaults, profiles and templates
+fn show_create_profile_dialog_with_source(
+    app_state: Rc<RefCell<AppState>>,
+    title: &str,
+    initial_name: Option<&str>,
+    initial_source: ProfileSource,
+) {
     let main_window = app_state.borrow().main_window.clone();
+    let templates = load_templates().unwrap_or_else(|e| {
+        eprintln!("Error loading templates: {}", e);
+        TemplateFile::default()
+    });
     
     let main_window_clone = main_window.clone();
     let (dialog, entry, _browse_button) = DialogFactory::create_input_dialog_with_browse(
         &main_window,
-        "New Profile",
+        title,
         "Enter or browse Application Name",
         "Application Name",
-        None,
+        initial_name,
         "application-x-executable-symbolic",
         "Pick a running Vulkan process",
         move |entry: &Entry| {
             show_process_picker_with_parent(entry, &main_window_clone);
         },
     );
+
+    // "Based on" selector
+    let sources = list_profile_sources(&app_state.borrow().config, &templates);
+    let labels: Vec<String> = {
+        let state = app_state.borrow();
+        sources.iter().map(|source| source.label(&state.config, &templates)).collect()
+    };
+    let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let source_dropdown = DropDown::from_strings(&label_refs);
+    let initial_position = sources.iter().position(|source| *source == initial_source).unwrap_or(0);
+    source_dropdown.set_selected(initial_position as u32);
+    dialog.content_area().append(&LayoutFactory::create_labeled_row("Based on", &source_dropdown));
     
     let app_state_clone = app_state.clone();
     let entry_clone = entry.clone();
     dialog.connect_response(move |d, response| {
         if response == gtk::ResponseType::Other(1) {
             let profile_name = entry_clone.text().to_string();
-            match handle_create_profile(&app_state_clone, &profile_name) {
+            let base = {
+                let state = app_state_clone.borrow();
+                sources.get(source_dropdown.selected() as usize)
+                    .map(|source| source.resolve(&state.config, &templates))
+                    .unwrap_or_default()
+            };
+            match handle_create_profile(&app_state_clone, &profile_name, base) {
                 Ok(_) => d.close(),
                 Err(error_msg) => {
                     show_profile_error(d, &error_msg);
@@ -78,17 +136,29 @@ pub fn show_create_profile_dialog(app_state: Rc<RefCell<AppState>>) {
     dialog.present();
 }
 
+/// Returns a name derived from `base` that no existing profile uses yet
+pub fn unique_profile_name(config: &Config, base: &str) -> String {
+    let candidate = format!("{} (copy)", base);
+    if validate_profile_name(config, &candidate, None).is_ok() {
+        return candidate;
+    }
+    (2..)
+        .map(|n| format!("{} (copy {})", base, n))
+        .find(|name| validate_profile_name(config, name, None).is_ok())
+        .unwrap_or(candidate)
+}
+
 /// Handles the actual profile creation logic
-fn handle_create_profile(app_state: &Rc<RefCell<AppState>>, profile_name: &str) -> Result<(), String> {
+fn handle_create_profile(app_state: &Rc<RefCell<AppState>>, profile_name: &str, base: GameProfile) -> Result<(), String> {
     let mut state = app_state.borrow_mut();
     
     // Validate profile name
     validate_profile_name(&state.config, profile_name, None)?;
     
-    // Create new profile
+    // Create new profile from the chosen base settings
     let new_profile = GameProfile {
-        exe: profile_name.to_string(),
-        ..Default::default()
+        exe: profile_name.trim().to_string(),
+        ..base
     };
     
     state.config.game.push(new_profile);
@@ -108,9 +178,41 @@ fn handle_create_profile(app_state: &Rc<RefCell<AppState>>, profile_name: &str)
     Ok(())
 }
 
+/// Shows a dialog asking for a template name and stores the selected profile's settings under it
+pub fn show_save_template_dialog(app_state: Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let Some(profile) = state.selected_profile_index.and_then(|i| state.config.game.get(i)).cloned() else {
+        return;
+    };
+    drop(state);
+
+    let (dialog, entry) = DialogFactory::create_input_dialog(
+        &main_window,
+        "Save as Template",
+        "Store the current settings as a reusable template:",
+        "Template Name",
+        None,
+    );
+
+    let entry_clone = entry.clone();
+    dialog.connect_response(move |d, response| {
+        if response == gtk::ResponseType::Other(1) {
+            match save_profile_as_template(&entry_clone.text(), &profile) {
+                Ok(_) => d.close(),
+                Err(error_msg) => show_profile_error(d, &error_msg),
+            }
+        } else {
+            d.close();
+        }
+    });
+
+    dialog.present();
+}
+
 /// Shows the process picker dialog
 fn show_process_picker(entry: &Entry) {
-    let processes = get_vulkan_processes();
+    let processes = get_vulkan_processes().iter().map(VulkanProcess::describe).collect();
     
     // Try to get the main window, but handle the case where it fails
     let main_window = match entry.root().and_then(|root| root.downcast::<ApplicationWindow>().ok()) {
@@ -145,8 +247,8 @@ fn show_process_picker(entry: &Entry) {
 }
 
 /// Shows the process picker dialog with a provided parent window
-fn show_process_picker_with_parent(entry: &Entry, parent: &ApplicationWindow) {
-    let processes = get_vulkan_processes();
+pub fn show_process_picker_with_parent(entry: &Entry, parent: &ApplicationWindow) {
+    let processes = get_vulkan_processes().iter().map(VulkanProcess::describe).collect();
     let (picker_window, process_list_box) = ProcessPickerFactory::create_process_picker_window(parent, processes);
     
     // Connect selection handler
@@ -214,17 +316,79 @@ pub fn show_edit_profile_dialog(app_state: Rc<RefCell<AppState>>, profile_index:
 fn handle_edit_profile(app_state: &Rc<RefCell<AppState>>, profile_index: usize, new_name: &str) -> Result<(), String> {
     let mut state = app_state.borrow_mut();
     
+    if is_default_profile(&state.config.game[profile_index]) {
+        return Err("The default profile cannot be renamed".to_string());
+    }
+
     // Validate profile name
     validate_profile_name(&state.config, new_name, Some(profile_index))?;
     
-    // Update profile name
-    state.config.game[profile_index].exe = new_name.to_string();
+    // Update profile name, carrying its metadata along
+    let old_name = std::mem::replace(&mut state.config.game[profile_index].exe, new_name.to_string());
+    state.profile_meta.rename_profile(&old_name, new_name);
     state.save_current_config();
     state.populate_sidebar_with_handlers(Some(app_state.clone()));
     
     Ok(())
 }
 
+/// Moves a profile in conf.toml and rebuilds the sidebar. Reordering needs the
+/// sidebar in file order without a search or filter, since otherwise the
+/// result is hidden.
+pub fn reorder_profile(app_state: &Rc<RefCell<AppState>>, from: usize, to: usize) -> Result<(), String> {
+    let mut state = app_state.borrow_mut();
+    if state.sidebar_sort_mode() != SortMode::FileOrder {
+        return Err("Sort the sidebar by file order to reorder profiles".to_string());
+    }
+    let filter = state.sidebar_filter();
+    if !filter.query.trim().is_empty() {
+        return Err("Clear the search to reorder profiles".to_string());
+    }
+    if !filter.chips.is_empty() || !filter.tags.is_empty() {
+        return Err("Clear the filters to reorder profiles".to_string());
+    }
+    state.move_profile(from, to)?;
+    state.populate_sidebar_with_handlers(Some(app_state.clone()));
+    Ok(())
+}
+
+/// Moves the selected profile past its neighbour in the sidebar (`offset` -1 or 1)
+pub fn move_selected_profile(app_state: &Rc<RefCell<AppState>>, offset: isize) {
+    let state = app_state.borrow();
+    let Some(index) = state.selected_profile_index else {
+        return;
+    };
+    // Profiles in collapsed groups are skipped, so the move is always visible.
+    // Keyboard moves stay within the profile's group; dragging can move between groups.
+    let order = state.visible_profile_order();
+    let group_of = |index: usize| profile_group(&state.config.game[index], &state.profile_meta);
+    let target = order.iter()
+        .position(|i| *i == index)
+        .and_then(|position| position.checked_add_signed(offset))
+        .and_then(|position| order.get(position).copied())
+        .filter(|target| group_of(*target) == group_of(index));
+    let main_settings_box = state.main_settings_box.clone();
+    drop(state);
+
+    let Some(target) = target else {
+        return;
+    };
+    if let Err(e) = reorder_profile(app_state, index, target) {
+        FeedbackUtils::show_temporary_feedback(&main_settings_box, &e, 3);
+        return;
+    }
+
+    // Keep keyboard focus on the moved row once the rebuilt sidebar has selected it
+    let app_state_clone = app_state.clone();
+    glib::idle_add_local(move || {
+        let state = app_state_clone.borrow();
+        if let Some(row) = state.selected_profile_index.and_then(|index| state.sidebar_rows.borrow().get(index).cloned()) {
+            row.grab_focus();
+        }
+        glib::ControlFlow::Break
+    });
+}
+
 /// Shows the remove profile confirmation dialog
 pub fn show_remove_profile_dialog(app_state: Rc<RefCell<AppState>>, profile_index: usize) {
     let state = app_state.borrow();
@@ -242,7 +406,7 @@ pub fn show_remove_profile_dialog(app_state: Rc<RefCell<AppState>>, profile_inde
     let app_state_clone = app_state.clone();
     dialog.connect_response(move |d, response| {
         if response == gtk::ResponseType::Other(1) {
-            handle_remove_profile(&app_state_clone, profile_index);
+            remove_profiles(&app_state_clone, &[profile_index]);
         }
         d.close();
     });
@@ -251,23 +415,42 @@ pub fn show_remove_profile_dialog(app_state: Rc<RefCell<AppState>>, profile_inde
 }
 
 /// Handles the actual profile removal logic
-fn handle_remove_profile(app_state: &Rc<RefCell<AppState>>, profile_index: usize) {
+/// Removes profiles by config index and saves once
+pub fn remove_profiles(app_state: &Rc<RefCell<AppState>>, profile_indices: &[usize]) {
     let mut state = app_state.borrow_mut();
-    
-    // Remove the profile
-    state.config.game.remove(profile_index);
-    
+
+    // The pinned default profile is never removed
+    let mut indices: Vec<usize> = profile_indices.iter()
+        .copied()
+        .filter(|index| state.config.game.get(*index).is_some_and(|profile| !is_default_profile(profile)))
+        .collect();
+    if indices.is_empty() {
+        return;
+    }
+    indices.sort_unstable();
+    indices.dedup();
+
+    // Remove from the back so earlier indices stay valid; profiles inheriting
+    // from a removed one keep their current values
+    for index in indices.iter().rev() {
+        let removed = state.config.game.remove(*index);
+        if let Some(files) = state.profile_meta.get(&removed.exe).and_then(|meta| meta.launcher.as_ref()) {
+            remove_launcher(files);
+        }
+        state.profile_meta.remove_profile(&removed.exe);
+    }
+
     // Update selected index if needed
     if let Some(selected) = state.selected_profile_index {
-        if selected == profile_index {
+        if indices.contains(&selected) {
             // If we removed the selected profile, select the first available or none
             state.selected_profile_index = if state.config.game.is_empty() { None } else { Some(0) };
-        } else if selected > profile_index {
-            // Adjust index if we removed a profile before the selected one
-            state.selected_profile_index = Some(selected - 1);
+        } else {
+            // Adjust index for the profiles removed before the selected one
+            state.selected_profile_index = Some(selected - indices.iter().filter(|index| **index < selected).count());
         }
     }
-    
+
     state.save_current_config();
     state.populate_sidebar_with_handlers(Some(app_state.clone()));
     drop(state);
@@ -276,6 +459,68 @@ fn handle_remove_profile(app_state: &Rc<RefCell<AppState>>, profile_index: usize
     app_state.borrow().update_main_window_from_profile();
 }
 
+/// Asks for the selected profile's value of a field outside its dropdown list,
+/// e.g. a large multiplier; cancelling restores the previous selection
+pub fn show_custom_value_dialog(app_state: Rc<RefCell<AppState>>, field: ProfileField) {
+    let state = app_state.borrow();
+    let main_window = state.main_window.clone();
+    let current = state.selected_profile_index
+        .and_then(|index| state.config.game.get(index))
+        .map(|profile| field.value(profile));
+    drop(state);
+
+    ask_custom_value(&main_window, field, current.as_ref(), move |value| {
+        if let Some(value) = value {
+            update_profile_field(&app_state, |profile| {
+                field.set_value(profile, FieldValue::Choice(Some(value)))
+            });
+        }
+        app_state.borrow().update_main_window_from_profile();
+    });
+}
+
+/// Asks for a whole number in the field's custom range; `on_response` gets
+/// `None` if the dialog is cancelled
+pub fn ask_custom_value<F: Fn(Option<String>) + 'static>(
+    main_window: &ApplicationWindow,
+    field: ProfileField,
+    current: Option<&FieldValue>,
+    on_response: F,
+) {
+    let descriptor = field.descriptor();
+    let FieldKind::Choice { custom_range: Some((min, max)), .. } = descriptor.kind else {
+        return;
+    };
+    let current = match current {
+        Some(FieldValue::Choice(Some(value))) => value.parse::<u32>().unwrap_or(min),
+        _ => min,
+    };
+
+    let dialog = DialogFactory::create_message_dialog(
+        main_window,
+        &format!("Custom {}", descriptor.title),
+        &format!("Enter any whole number from {} to {}.", min, max),
+        gtk::MessageType::Question,
+    );
+    let spin_button = gtk::SpinButton::with_range(min as f64, max as f64, 1.0);
+    spin_button.set_value(current as f64);
+    spin_button.set_margin_start(12);
+    spin_button.set_margin_end(12);
+    dialog.content_area().append(&spin_button);
+    dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+    dialog.add_button("Set", gtk::ResponseType::Ok);
+    dialog.set_default_response(gtk::ResponseType::Ok);
+
+    dialog.connect_response(move |d, response| {
+        let value = (response == gtk::ResponseType::Ok)
+            .then(|| spin_button.value_as_int().max(min as i32).to_string());
+        on_response(value);
+        d.close();
+    });
+
+    dialog.present();
+}
+
 /// Updates a profile field with validation
 pub fn update_profile_field<F>(
     app_state: &Rc<RefCell<AppState>>,
@@ -286,9 +531,14 @@ pub fn update_profile_field<F>(
     let mut state = app_state.borrow_mut();
     if let Some(index) = state.selected_profile_index {
         if let Some(profile) = state.config.game.get_mut(index) {
+            let before = profile.clone();
             if let Err(e) = field_updater(profile) {
                 eprintln!("Error updating profile field: {}", e);
             }
+            state.apply_profile_edit(index, &before);
         }
     }
+    drop(state);
+
+    app_state.borrow().refresh_profile_indicators();
 }
\ No newline at end of file
@@ -0,0 +1,358 @@
+//! UI-only profile metadata
+//! Everything lsfg-vk cannot read itself lives in `profiles-meta.toml` next to
+//! conf.toml, keyed by the profile's executable name, so conf.toml stays clean.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::{fs, io};
+use std::path::PathBuf;
+
+use crate::config::{get_config_dir, Config, GameProfile};
+use crate::profile_fields::ProfileField;
+use crate::launcher::LaunchSettings;
+use crate::desktop_launcher::LauncherFiles;
+use crate::gamescope::GamescopeSettings;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct ProfileMetaFile {
+    #[serde(default)]
+    pub profile: BTreeMap<String, ProfileMeta>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProfileMeta {
+    /// Name of the profile this one takes its non-overridden values from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<String>,
+    /// Keys of the fields this profile sets itself instead of inheriting
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<String>,
+    /// Glob patterns matched against the executable name, or the full path if they contain '/'
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    /// Set on concrete entries generated from another profile's patterns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expanded_from: Option<String>,
+    /// How to start the game from the UI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<LaunchSettings>,
+    /// Steam AppID the profile's launch options are written for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steam_app_id: Option<String>,
+    /// Wrapper script and desktop entry generated for the profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launcher: Option<LauncherFiles>,
+    /// Gamescope settings put in front of generated launch commands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gamescope: Option<GamescopeSettings>,
+    /// Free-text notes, included in the sidebar search
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    /// Labels the sidebar can filter by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    /// Collapsible sidebar section the profile is listed under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Unix time of the last settings change, for sorting by recently edited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited_at: Option<u64>,
+}
+
+impl ProfileMeta {
+    pub fn is_empty(&self) -> bool {
+        *self == ProfileMeta::default()
+    }
+
+    pub fn is_overridden(&self, field: ProfileField) -> bool {
+        self.overrides.iter().any(|key| key == field.key())
+    }
+
+    pub fn set_overridden(&mut self, field: ProfileField, overridden: bool) {
+        self.overrides.retain(|key| key != field.key());
+        if overridden {
+            self.overrides.push(field.key().to_string());
+        }
+    }
+}
+
+impl ProfileMetaFile {
+    pub fn get(&self, exe: &str) -> Option<&ProfileMeta> {
+        self.profile.get(exe)
+    }
+
+    pub fn entry(&mut self, exe: &str) -> &mut ProfileMeta {
+        self.profile.entry(exe.to_string()).or_default()
+    }
+
+    /// Returns the base profile name if the given profile inherits from one
+    pub fn inherits(&self, exe: &str) -> Option<&str> {
+        self.get(exe).and_then(|meta| meta.inherits.as_deref())
+    }
+
+    /// Returns true if the field is inherited rather than set by the profile itself
+    pub fn is_inherited(&self, exe: &str, field: ProfileField) -> bool {
+        self.get(exe).is_some_and(|meta| meta.inherits.is_some() && !meta.is_overridden(field))
+    }
+
+    /// Moves metadata to a new key and repoints profiles inheriting from the old name
+    pub fn rename_profile(&mut self, old_exe: &str, new_exe: &str) {
+        if let Some(meta) = self.profile.remove(old_exe) {
+            self.profile.insert(new_exe.to_string(), meta);
+        }
+        for meta in self.profile.values_mut() {
+            if meta.inherits.as_deref() == Some(old_exe) {
+                meta.inherits = Some(new_exe.to_string());
+            }
+            if meta.expanded_from.as_deref() == Some(old_exe) {
+                meta.expanded_from = Some(new_exe.to_string());
+            }
+        }
+    }
+
+    /// Drops metadata of a removed profile; children keep their flattened values
+    pub fn remove_profile(&mut self, exe: &str) {
+        self.profile.remove(exe);
+        for meta in self.profile.values_mut() {
+            if meta.inherits.as_deref() == Some(exe) {
+                meta.inherits = None;
+                meta.overrides.clear();
+            }
+            if meta.expanded_from.as_deref() == Some(exe) {
+                meta.expanded_from = None;
+            }
+        }
+        self.prune();
+    }
+
+    /// Removes entries that no longer carry any information
+    pub fn prune(&mut self) {
+        self.profile.retain(|_, meta| !meta.is_empty());
+    }
+}
+
+// --- Inheritance ---
+
+/// Resolves the effective values of a profile by walking its inheritance chain
+pub fn resolve_profile(config: &Config, meta: &ProfileMetaFile, exe: &str) -> Option<GameProfile> {
+    resolve_profile_chain(config, meta, exe, &mut Vec::new())
+}
+
+fn resolve_profile_chain(
+    config: &Config,
+    meta: &ProfileMetaFile,
+    exe: &str,
+    chain: &mut Vec<String>,
+) -> Option<GameProfile> {
+    let mut resolved = config.game.iter().find(|p| p.exe == exe)?.clone();
+    let Some(entry) = meta.get(exe) else {
+        return Some(resolved);
+    };
+    let Some(base_name) = entry.inherits.as_deref() else {
+        return Some(resolved);
+    };
+
+    if chain.iter().any(|name| name == exe) {
+        eprintln!("Inheritance cycle detected at profile '{}'", exe);
+        return Some(resolved);
+    }
+    chain.push(exe.to_string());
+
+    if let Some(base) = resolve_profile_chain(config, meta, base_name, chain) {
+        for field in ProfileField::all() {
+            if !entry.is_overridden(field) {
+                field.copy_value(&base, &mut resolved);
+            }
+        }
+    }
+    Some(resolved)
+}
+
+/// Writes inherited values into every profile so conf.toml only holds plain,
+/// self-contained [[game]] entries that lsfg-vk can read
+pub fn flatten_inheritance(config: &mut Config, meta: &ProfileMetaFile) {
+    let names: Vec<String> = config.game.iter()
+        .filter(|p| meta.inherits(&p.exe).is_some())
+        .map(|p| p.exe.clone())
+        .collect();
+
+    for name in names {
+        if let Some(resolved) = resolve_profile(config, meta, &name) {
+            if let Some(profile) = config.game.iter_mut().find(|p| p.exe == name) {
+                *profile = resolved;
+            }
+        }
+    }
+}
+
+/// Records an edit of `exe`: if it inherits, the changed fields become its own
+/// overrides. Every profile is then flattened again, so children of `exe` pick
+/// up the new values.
+pub fn record_profile_edit(config: &mut Config, meta: &mut ProfileMetaFile, exe: &str, changed: &[ProfileField]) {
+    if meta.inherits(exe).is_some() {
+        let entry = meta.entry(exe);
+        for field in changed {
+            entry.set_overridden(*field, true);
+        }
+    }
+    flatten_inheritance(config, meta);
+}
+
+/// Returns true if making `exe` inherit from `base` would create a cycle
+pub fn would_create_cycle(meta: &ProfileMetaFile, exe: &str, base: &str) -> bool {
+    let mut current = Some(base);
+    let mut steps = 0;
+    while let Some(name) = current {
+        if name == exe || steps > meta.profile.len() {
+            return true;
+        }
+        current = meta.inherits(name);
+        steps += 1;
+    }
+    false
+}
+
+// --- Metadata File Handling Functions ---
+
+pub fn get_profile_meta_path() -> Result<PathBuf, io::Error> {
+    Ok(get_config_dir()?.join("profiles-meta.toml"))
+}
+
+pub fn load_profile_meta() -> Result<ProfileMetaFile, io::Error> {
+    let path = get_profile_meta_path()?;
+    if !path.exists() {
+        return Ok(ProfileMetaFile::default());
+    }
+    let contents = fs::read_to_string(&path)?;
+    toml::from_str(&contents).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("Failed to parse profile metadata: {}", e))
+    })
+}
+
+pub fn save_profile_meta(meta: &ProfileMetaFile) -> Result<(), io::Error> {
+    let path = get_profile_meta_path()?;
+    let toml_string = toml::to_string_pretty(meta)
+        .map_err(|e| io::Error::other(format!("Failed to serialize profile metadata: {}", e)))?;
+    fs::write(&path, toml_string)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile_fields::changed_fields;
+
+    fn profile(exe: &str, multiplier: u32, flow_scale: f32) -> GameProfile {
+        GameProfile { exe: exe.to_string(), multiplier, flow_scale, ..GameProfile::default() }
+    }
+
+    // "base" → "child" → "grandchild", where the child sets its own flow scale
+    fn family() -> (Config, ProfileMetaFile) {
+        let config = Config {
+            game: vec![profile("base", 2, 0.5), profile("child", 1, 0.8), profile("grandchild", 1, 1.0)],
+            ..Config::default()
+        };
+        let mut meta = ProfileMetaFile::default();
+        meta.entry("child").inherits = Some("base".to_string());
+        meta.entry("child").set_overridden(ProfileField::FlowScale, true);
+        meta.entry("grandchild").inherits = Some("child".to_string());
+        (config, meta)
+    }
+
+    fn find<'a>(config: &'a Config, exe: &str) -> &'a GameProfile {
+        config.game.iter().find(|profile| profile.exe == exe).unwrap()
+    }
+
+    #[test]
+    fn flattening_pushes_parent_values_down_the_chain() {
+        let (mut config, meta) = family();
+        flatten_inheritance(&mut config, &meta);
+
+        assert_eq!((find(&config, "child").multiplier, find(&config, "child").flow_scale), (2, 0.8));
+        assert_eq!((find(&config, "grandchild").multiplier, find(&config, "grandchild").flow_scale), (2, 0.8));
+        assert_eq!(find(&config, "base").multiplier, 2);
+    }
+
+    #[test]
+    fn overrides_survive_a_parent_change() {
+        let (mut config, mut meta) = family();
+        flatten_inheritance(&mut config, &meta);
+
+        let before = find(&config, "base").clone();
+        let base = config.game.iter_mut().find(|profile| profile.exe == "base").unwrap();
+        base.multiplier = 4;
+        base.flow_scale = 0.25;
+        let changed = changed_fields(&before, find(&config, "base"));
+        record_profile_edit(&mut config, &mut meta, "base", &changed);
+
+        assert_eq!((find(&config, "child").multiplier, find(&config, "child").flow_scale), (4, 0.8));
+        assert_eq!((find(&config, "grandchild").multiplier, find(&config, "grandchild").flow_scale), (4, 0.8));
+        // The base inherits nothing, so its edit is not an override
+        assert!(meta.get("base").is_none());
+    }
+
+    #[test]
+    fn editing_an_inherited_field_makes_it_an_override() {
+        let (mut config, mut meta) = family();
+        flatten_inheritance(&mut config, &meta);
+
+        let before = find(&config, "grandchild").clone();
+        config.game.iter_mut().find(|profile| profile.exe == "grandchild").unwrap().multiplier = 3;
+        let changed = changed_fields(&before, find(&config, "grandchild"));
+        record_profile_edit(&mut config, &mut meta, "grandchild", &changed);
+
+        assert!(meta.get("grandchild").unwrap().is_overridden(ProfileField::Multiplier));
+        assert!(!meta.is_inherited("grandchild", ProfileField::Multiplier));
+        assert!(meta.is_inherited("grandchild", ProfileField::FlowScale));
+
+        config.game.iter_mut().find(|profile| profile.exe == "base").unwrap().multiplier = 5;
+        flatten_inheritance(&mut config, &meta);
+        assert_eq!(find(&config, "grandchild").multiplier, 3);
+        assert_eq!(find(&config, "child").multiplier, 5);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let (_, meta) = family();
+        assert!(would_create_cycle(&meta, "base", "grandchild"));
+        assert!(would_create_cycle(&meta, "base", "base"));
+        assert!(!would_create_cycle(&meta, "grandchild", "base"));
+        assert!(!would_create_cycle(&meta, "base", "other"));
+    }
+
+    #[test]
+    fn resolving_a_cycle_stops_instead_of_looping() {
+        let (config, mut meta) = family();
+        meta.entry("base").inherits = Some("grandchild".to_string());
+        let resolved = resolve_profile(&config, &meta, "child").unwrap();
+        assert_eq!(resolved.flow_scale, 0.8);
+    }
+
+    #[test]
+    fn renaming_repoints_children() {
+        let (_, mut meta) = family();
+        meta.entry("expanded.exe").expanded_from = Some("child".to_string());
+        meta.rename_profile("child", "renamed");
+
+        assert!(meta.get("child").is_none());
+        assert_eq!(meta.inherits("renamed"), Some("base"));
+        assert_eq!(meta.inherits("grandchild"), Some("renamed"));
+        assert_eq!(meta.get("expanded.exe").unwrap().expanded_from.as_deref(), Some("renamed"));
+    }
+
+    #[test]
+    fn removing_detaches_children() {
+        let (mut config, mut meta) = family();
+        flatten_inheritance(&mut config, &meta);
+        meta.entry("grandchild").set_overridden(ProfileField::HdrMode, true);
+        meta.remove_profile("child");
+        config.game.retain(|profile| profile.exe != "child");
+
+        // Flattened values stay, but nothing is inherited any more
+        assert!(meta.get("grandchild").is_none());
+        flatten_inheritance(&mut config, &meta);
+        assert_eq!((find(&config, "grandchild").multiplier, find(&config, "grandchild").flow_scale), (2, 0.8));
+    }
+}
@@ -3,9 +3,10 @@ use gtk::{glib, Switch, Button};
 use libadwaita::prelude::*;
 use libadwaita::{ApplicationWindow, PreferencesGroup, PreferencesPage, PreferencesWindow, ActionRow, Toast, ToastOverlay, HeaderBar, WindowTitle};
 use std::rc::Rc;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 use crate::app_state::AppState;
+use crate::global_options::{GlobalOption, GlobalValue, GLOBAL_OPTIONS};
 
 pub fn create_settings_window(parent: &ApplicationWindow, app_state: Rc<RefCell<AppState>>) -> PreferencesWindow {
     let settings_window = PreferencesWindow::builder()
@@ -56,98 +57,19 @@ fn create_global_settings_page(app_state: Rc<RefCell<AppState>>, toast_overlay:
 
     let group = PreferencesGroup::builder()
         .title("Global Settings")
-        .description("Configure global application settings")
+        .description("Options in the [global] table of conf.toml. Unset options use lsfg-vk's default.")
         .build();
 
-    // --- Custom DLL Toggle and Path using modern libadwaita components ---
-    let custom_dll_switch = Switch::builder()
-        .halign(gtk::Align::End)
-        .valign(gtk::Align::Center)
-        .build();
-
-    let custom_dll_row = ActionRow::builder()
-        .title("Use Custom Lossless.dll")
-        .subtitle("Enable to specify a custom path to the Lossless.dll file")
-        .build();
-    custom_dll_row.add_suffix(&custom_dll_switch);
-    custom_dll_row.set_activatable_widget(Some(&custom_dll_switch));
-
-    let custom_dll_path_row = ActionRow::builder()
-        .title("DLL Path")
-        .subtitle("Path to the custom Lossless.dll file")
-        .sensitive(false)
-        .build();
-    
-    let custom_dll_entry = gtk::Entry::builder()
-        .placeholder_text("/path/to/Lossless.dll")
-        .hexpand(true)
-        .sensitive(false)
-        .build();
-    
-    custom_dll_path_row.add_suffix(&custom_dll_entry);
-
-    group.add(&custom_dll_row);
-    group.add(&custom_dll_path_row);
-
-    // Initial state setup for Custom DLL
-    let current_dll_path = app_state.borrow().config.ordered_global.global.as_ref()
-        .and_then(|g| g.dll.clone());
-
-    if let Some(path) = current_dll_path {
-        custom_dll_switch.set_active(true);
-        custom_dll_entry.set_text(&path);
-        custom_dll_path_row.set_sensitive(true);
-        custom_dll_entry.set_sensitive(true);
-    } else {
-        custom_dll_switch.set_active(false);
-        custom_dll_path_row.set_sensitive(false);
-        custom_dll_entry.set_sensitive(false);
+    // One generated row per [global] option
+    for option in GLOBAL_OPTIONS {
+        add_global_option_row(&group, option, app_state.clone());
     }
 
-    // Connect switch to enable/disable entry and update config
-    let app_state_clone_switch = app_state.clone();
-    let custom_dll_path_row_clone = custom_dll_path_row.clone();
-    let custom_dll_entry_clone = custom_dll_entry.clone();
-    custom_dll_switch.connect_state_set(move |_sw, active| {
-        custom_dll_path_row_clone.set_sensitive(active);
-        custom_dll_entry_clone.set_sensitive(active);
-        let mut state = app_state_clone_switch.borrow_mut();
-        if active {
-            // If activating, ensure global config exists and set DLL path
-            let current_path = custom_dll_entry_clone.text().to_string();
-            state.config.ordered_global.global.get_or_insert_with(Default::default).dll = Some(current_path);
-        } else {
-            // If deactivating, set DLL path to None
-            if let Some(global_config) = state.config.ordered_global.global.as_mut() {
-                global_config.dll = None;
-            }
-        }
-        glib::Propagation::Proceed
-    });
-
-    // Connect entry to update config
-    let app_state_clone_entry = app_state.clone();
-    let custom_dll_switch_clone = custom_dll_switch.clone();
-    custom_dll_entry.connect_changed(move |entry| {
-        let mut state = app_state_clone_entry.borrow_mut();
-        if custom_dll_switch_clone.is_active() {
-            let path = entry.text().to_string();
-            if !path.is_empty() {
-                state.config.ordered_global.global.get_or_insert_with(Default::default).dll = Some(path);
-            } else {
-                // If path is cleared, set dll to None
-                if let Some(global_config) = state.config.ordered_global.global.as_mut() {
-                    global_config.dll = None;
-                }
-            }
-        }
-    });
-
     // Connect save button
     let app_state_clone_save = app_state.clone();
     let toast_overlay_clone = toast_overlay.clone();
     save_button.connect_clicked(move |_| {
-        let state = app_state_clone_save.borrow();
+        let mut state = app_state_clone_save.borrow_mut();
         state.save_current_config();
         
         // Show toast notification
@@ -162,6 +84,99 @@ fn create_global_settings_page(app_state: Rc<RefCell<AppState>>, toast_overlay:
     page
 }
 
+/// Builds the row for one global option: a switch or entry plus a "reset to default" button
+fn add_global_option_row(group: &PreferencesGroup, option: &'static GlobalOption, app_state: Rc<RefCell<AppState>>) {
+    let row = ActionRow::builder()
+        .title(option.title)
+        .subtitle(option.description)
+        .build();
+    row.set_tooltip_text(Some(&format!("[global] {}", option.key)));
+
+    let reset_button = Button::builder()
+        .icon_name("edit-undo-symbolic")
+        .tooltip_text("Reset to default")
+        .css_classes(["flat", "circular"])
+        .valign(gtk::Align::Center)
+        .build();
+    row.add_suffix(&reset_button);
+
+    let global = app_state.borrow().config.ordered_global.global.clone();
+    reset_button.set_visible(option.is_set(global.as_ref()));
+
+    // Guards against widget signals while the row itself updates the widget
+    let updating = Rc::new(Cell::new(false));
+    let store_value = {
+        let app_state = app_state.clone();
+        let reset_button = reset_button.clone();
+        move |value: Option<GlobalValue>| {
+            let mut state = app_state.borrow_mut();
+            let global = state.config.ordered_global.global.get_or_insert_with(Default::default);
+            (option.set)(global, value);
+            let is_set = option.is_set(Some(global));
+            // Drop an empty [global] table entirely
+            if global.extra.is_empty() && GLOBAL_OPTIONS.iter().all(|option| !option.is_set(Some(global))) {
+                state.config.ordered_global.global = None;
+            }
+            reset_button.set_visible(is_set);
+        }
+    };
+
+    let reset_widget: Rc<dyn Fn()> = match option.effective_value(global.as_ref()) {
+        GlobalValue::Bool(active) => {
+            let switch = Switch::builder()
+                .active(active)
+                .valign(gtk::Align::Center)
+                .build();
+            row.add_suffix(&switch);
+            row.set_activatable_widget(Some(&switch));
+
+            let updating_clone = updating.clone();
+            let store_value = store_value.clone();
+            switch.connect_state_set(move |_sw, active| {
+                if !updating_clone.get() {
+                    store_value(Some(GlobalValue::Bool(active)));
+                }
+                glib::Propagation::Proceed
+            });
+
+            let default = matches!(option.default, GlobalValue::Bool(true));
+            Rc::new(move || switch.set_active(default))
+        }
+        GlobalValue::Text(text) => {
+            let entry = gtk::Entry::builder()
+                .text(text)
+                .placeholder_text(option.placeholder)
+                .hexpand(true)
+                .valign(gtk::Align::Center)
+                .build();
+            row.add_suffix(&entry);
+
+            let updating_clone = updating.clone();
+            let store_value = store_value.clone();
+            entry.connect_changed(move |entry| {
+                if !updating_clone.get() {
+                    store_value(Some(GlobalValue::Text(entry.text().to_string())));
+                }
+            });
+
+            let default = match &option.default {
+                GlobalValue::Text(text) => text.clone(),
+                GlobalValue::Bool(_) => String::new(),
+            };
+            Rc::new(move || entry.set_text(&default))
+        }
+    };
+
+    reset_button.connect_clicked(move |_| {
+        updating.set(true);
+        reset_widget();
+        updating.set(false);
+        store_value(None);
+    });
+
+    group.add(&row);
+}
+
 fn create_about_page() -> PreferencesPage {
     let page = PreferencesPage::builder()
         .title("About")
@@ -0,0 +1,230 @@
+//! Search, filter and sort for the profile sidebar
+//! Only decides which profiles are listed and in what order; the rows
+//! themselves are built once per config change and re-arranged from this.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashSet};
+
+use crate::config::{Config, GameProfile};
+use crate::default_profile::{is_default_profile, DEFAULT_PROFILE_LABEL};
+use crate::presets::find_matching_preset;
+use crate::profile_meta::{ProfileMeta, ProfileMetaFile};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    /// The order of `[[game]]` entries in conf.toml; the only mode that can be reordered
+    FileOrder,
+    Name,
+    RecentlyEdited,
+    Multiplier,
+}
+
+/// Sort modes in the order of the sidebar's sort dropdown
+pub const SORT_MODES: [(SortMode, &str); 4] = [
+    (SortMode::FileOrder, "File Order"),
+    (SortMode::Name, "Name"),
+    (SortMode::RecentlyEdited, "Recently Edited"),
+    (SortMode::Multiplier, "Multiplier"),
+];
+
+impl SortMode {
+    /// Stable name for the saved UI state
+    pub fn key(self) -> &'static str {
+        match self {
+            SortMode::FileOrder => "file_order",
+            SortMode::Name => "name",
+            SortMode::RecentlyEdited => "recently_edited",
+            SortMode::Multiplier => "multiplier",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<SortMode> {
+        SORT_MODES.iter().map(|(mode, _)| *mode).find(|mode| mode.key() == key)
+    }
+}
+
+/// Filter chips above the profile list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterChip {
+    FrameGenOn,
+    FrameGenOff,
+    Hdr,
+    /// Settings that match none of the built-in presets
+    Custom,
+    Favorite,
+}
+
+impl FilterChip {
+    pub const ALL: [FilterChip; 5] = [
+        FilterChip::FrameGenOn,
+        FilterChip::FrameGenOff,
+        FilterChip::Hdr,
+        FilterChip::Custom,
+        FilterChip::Favorite,
+    ];
+
+    /// Used for the chip's widget id, `filter_<key>_button`
+    pub fn key(self) -> &'static str {
+        match self {
+            FilterChip::FrameGenOn => "frame_gen_on",
+            FilterChip::FrameGenOff => "frame_gen_off",
+            FilterChip::Hdr => "hdr",
+            FilterChip::Custom => "custom",
+            FilterChip::Favorite => "favorite",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<FilterChip> {
+        FilterChip::ALL.into_iter().find(|chip| chip.key() == key)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SidebarFilter {
+    pub query: String,
+    pub chips: Vec<FilterChip>,
+    /// Tags a profile must all have
+    pub tags: Vec<String>,
+}
+
+impl SidebarFilter {
+    /// Frame generation on and off together filter nothing, as does neither
+    pub fn matches(&self, profile: &GameProfile, meta: Option<&ProfileMeta>) -> bool {
+        let active = |chip| self.chips.contains(&chip);
+        let favorite = meta.is_some_and(|meta| meta.favorite);
+        let tags: &[String] = meta.map_or(&[], |meta| &meta.tags);
+        let frame_gen = profile.multiplier > 1;
+        let frame_gen_ok = active(FilterChip::FrameGenOn) == active(FilterChip::FrameGenOff)
+            || (active(FilterChip::FrameGenOn) && frame_gen)
+            || (active(FilterChip::FrameGenOff) && !frame_gen);
+        frame_gen_ok
+            && (!active(FilterChip::Hdr) || profile.hdr_mode)
+            && (!active(FilterChip::Custom) || find_matching_preset(profile).is_none())
+            && (!active(FilterChip::Favorite) || favorite)
+            && self.tags.iter().all(|tag| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Search score of a profile, or `None` if it is filtered out
+    pub fn score(&self, profile: &GameProfile, meta: Option<&ProfileMeta>) -> Option<u32> {
+        if !self.matches(profile, meta) {
+            return None;
+        }
+        if self.query.trim().is_empty() {
+            return Some(0);
+        }
+        let name = if is_default_profile(profile) { DEFAULT_PROFILE_LABEL } else { &profile.exe };
+        let mut texts = vec![name, profile.exe.as_str()];
+        if let Some(meta) = meta {
+            texts.push(&meta.notes);
+            texts.extend(meta.tags.iter().map(String::as_str));
+            texts.extend(meta.group.as_deref());
+        }
+        texts.into_iter()
+            .filter_map(|text| fuzzy_score(self.query.trim(), text))
+            .max()
+    }
+}
+
+/// Scores `text` against a query whose characters must all appear in order.
+/// Runs of consecutive characters and matches at word starts score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|c| *c == query_char)?;
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous_match = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Indices into `config.game` of the profiles to list, in display order.
+/// The default profile stays on top whenever it matches; a search orders the
+/// rest by how well they match, falling back to the sort mode.
+pub fn sidebar_order(config: &Config, meta: &ProfileMetaFile, filter: &SidebarFilter, sort: SortMode) -> Vec<usize> {
+    let mut entries: Vec<(usize, u32)> = config.game.iter()
+        .enumerate()
+        .filter_map(|(index, profile)| filter.score(profile, meta.get(&profile.exe)).map(|score| (index, score)))
+        .collect();
+
+    let edited_at = |index: usize| meta.get(&config.game[index].exe).and_then(|meta| meta.edited_at);
+    let name = |index: usize| config.game[index].exe.to_lowercase();
+    match sort {
+        SortMode::FileOrder => {}
+        SortMode::Name => entries.sort_by_key(|(index, _)| name(*index)),
+        SortMode::RecentlyEdited => entries.sort_by_key(|(index, _)| (Reverse(edited_at(*index)), name(*index))),
+        SortMode::Multiplier => entries.sort_by_key(|(index, _)| (config.game[*index].multiplier, name(*index))),
+    }
+    entries.sort_by_key(|(index, score)| (!is_default_profile(&config.game[*index]), Reverse(*score)));
+    entries.into_iter().map(|(index, _)| index).collect()
+}
+
+/// Splits comma-separated tags, dropping empty and repeated ones
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Every tag used by a profile in the config, sorted, for the tag filter chips
+pub fn all_tags(config: &Config, meta: &ProfileMetaFile) -> Vec<String> {
+    let tags: BTreeSet<&String> = config.game.iter()
+        .filter_map(|profile| meta.get(&profile.exe))
+        .flat_map(|meta| &meta.tags)
+        .collect();
+    tags.into_iter().cloned().collect()
+}
+
+/// The sidebar group of a profile; the default profile is never grouped
+pub fn profile_group<'a>(profile: &GameProfile, meta: &'a ProfileMetaFile) -> Option<&'a str> {
+    if is_default_profile(profile) {
+        return None;
+    }
+    meta.get(&profile.exe).and_then(|meta| meta.group.as_deref())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SidebarItem {
+    /// Header of a group; `count` is the number of its profiles passing the filter
+    Group { name: String, count: usize, collapsed: bool },
+    Profile(usize),
+}
+
+/// Lays out ordered profiles as the sidebar shows them: ungrouped profiles
+/// first, then a header per group (sorted by name) followed by its profiles
+/// unless the group is collapsed. Order within each section is kept.
+pub fn sidebar_items(config: &Config, meta: &ProfileMetaFile, order: &[usize], collapsed: &HashSet<String>) -> Vec<SidebarItem> {
+    let group_of = |index: usize| profile_group(&config.game[index], meta);
+    let mut items: Vec<SidebarItem> = order.iter()
+        .filter(|index| group_of(**index).is_none())
+        .map(|index| SidebarItem::Profile(*index))
+        .collect();
+
+    let groups: BTreeSet<(String, &str)> = order.iter()
+        .filter_map(|index| group_of(*index))
+        .map(|group| (group.to_lowercase(), group))
+        .collect();
+    for (_, group) in groups {
+        let members: Vec<usize> = order.iter().copied().filter(|index| group_of(*index) == Some(group)).collect();
+        let is_collapsed = collapsed.contains(group);
+        items.push(SidebarItem::Group { name: group.to_string(), count: members.len(), collapsed: is_collapsed });
+        if !is_collapsed {
+            items.extend(members.into_iter().map(SidebarItem::Profile));
+        }
+    }
+    items
+}
@@ -1,183 +1,462 @@
 use gtk::prelude::*;
-use gtk::{glib, DropDown, Entry, Switch, StringObject};
-use std::cell::RefCell;
+use libadwaita::prelude::*;
+use gtk::{glib, DropDown, Entry, Switch};
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
+use crate::frame_math::detect_refresh_rate;
 use crate::app_state::AppState;
-use crate::utils::round_to_2_decimals;
 use crate::profile_manager::update_profile_field;
+use crate::presets::preset_catalog;
+use crate::profile_fields::ProfileField;
+use crate::profile_editor::EditorInput;
+use crate::exe_matching::{expand_patterns, parse_patterns};
+use crate::sidebar::parse_tags;
+use crate::utils::get_vulkan_processes;
 
 /// Connects all profile-related signal handlers and stores their IDs
 pub fn connect_profile_signal_handlers(app_state: &Rc<RefCell<AppState>>) {
     let mut state = app_state.borrow_mut();
     
-    // Connect multiplier dropdown handler
-    let multiplier_handler_id = connect_multiplier_dropdown_handler(
-        &state.multiplier_dropdown,
-        app_state,
-    );
-    state.multiplier_dropdown_handler_id = Some(multiplier_handler_id);
-    
-    // Connect flow scale entry handler
-    let flow_handler_id = connect_flow_scale_entry_handler(
-        &state.flow_scale_entry,
+    // Connect the generated field editors and their reset buttons
+    for editor in &mut state.field_editors {
+        let handler_id = connect_field_editor_handler(editor, app_state);
+        editor.handler_id = Some(handler_id);
+        connect_reset_button_handler(&editor.reset_button, editor.field, app_state);
+    }
+
+    // Connect preset dropdown handler
+    let preset_handler_id = connect_preset_dropdown_handler(
+        &state.preset_dropdown,
         app_state,
     );
-    state.flow_scale_entry_handler_id = Some(flow_handler_id);
-    
-    // Connect performance mode switch handler
-    let perf_handler_id = connect_performance_mode_switch_handler(
-        &state.performance_mode_switch,
+    state.preset_dropdown_handler_id = Some(preset_handler_id);
+
+    // Connect inheritance handlers
+    let inherits_handler_id = connect_inherits_dropdown_handler(
+        &state.inherits_dropdown,
         app_state,
     );
-    state.performance_mode_switch_handler_id = Some(perf_handler_id);
-    
-    // Connect HDR mode switch handler
-    let hdr_handler_id = connect_hdr_mode_switch_handler(
-        &state.hdr_mode_switch,
+    state.inherits_dropdown_handler_id = Some(inherits_handler_id);
+
+    // Connect executable pattern entry handler
+    let patterns_handler_id = connect_patterns_entry_handler(
+        &state.patterns_entry,
         app_state,
     );
-    state.hdr_mode_switch_handler_id = Some(hdr_handler_id);
-    
-    // Connect experimental present mode dropdown handler
-    let exp_handler_id = connect_experimental_present_mode_dropdown_handler(
-        &state.experimental_present_mode_dropdown,
+    state.patterns_entry_handler_id = Some(patterns_handler_id);
+
+    // Connect organization metadata handlers
+    state.favorite_switch_handler_id = Some(connect_favorite_switch_handler(&state.favorite_switch, app_state));
+    state.group_entry_handler_id = Some(connect_organization_entry_handler(&state.group_entry, app_state, |meta, text| {
+        let group = text.trim();
+        meta.group = (!group.is_empty()).then(|| group.to_string());
+    }));
+    state.tags_entry_handler_id = Some(connect_organization_entry_handler(&state.tags_entry, app_state, |meta, text| {
+        meta.tags = parse_tags(text);
+    }));
+    state.notes_buffer_handler_id = Some(connect_notes_buffer_handler(&state.notes_view.buffer(), app_state));
+
+    // Connect Steam AppID entry handler
+    let steam_handler_id = connect_steam_app_id_entry_handler(
+        &state.steam_app_id_entry,
         app_state,
     );
-    state.experimental_present_mode_dropdown_handler_id = Some(exp_handler_id);
+    state.steam_app_id_entry_handler_id = Some(steam_handler_id);
 }
 
-/// Connects multiplier dropdown signal handler
-fn connect_multiplier_dropdown_handler(
+/// Connects a generated field editor; edits go straight into the selected profile
+fn connect_field_editor_handler(
+    editor: &crate::profile_editor::FieldEditor,
+    app_state: &Rc<RefCell<AppState>>,
+) -> glib::SignalHandlerId {
+    let field = editor.field;
+    let app_state_clone = app_state.clone();
+    editor.widget.connect_changed(move |input| match input {
+        EditorInput::Value(value) => update_profile_field(&app_state_clone, |profile| field.set_value(profile, value)),
+        EditorInput::CustomRequested => crate::profile_manager::show_custom_value_dialog(app_state_clone.clone(), field),
+    })
+}
+
+/// Connects preset dropdown signal handler
+fn connect_preset_dropdown_handler(
     dropdown: &DropDown,
     app_state: &Rc<RefCell<AppState>>,
 ) -> glib::SignalHandlerId {
     let app_state_clone = app_state.clone();
-    dropdown.connect_selected_item_notify(move |dropdown| {
-        update_profile_field(&app_state_clone, |profile| {
-            if let Some(item) = dropdown.selected_item() {
-                if let Some(string_obj) = item.downcast_ref::<StringObject>() {
-                    let text = string_obj.string();
-                    profile.multiplier = match text.as_str() {
-                        "off" => 1,
-                        _ => text.parse().unwrap_or(1),
-                    };
-                }
-            }
-            Ok(())
-        });
+    dropdown.connect_selected_notify(move |dropdown| {
+        // Position 0 is "Custom", which leaves the profile untouched
+        let Some(preset_index) = (dropdown.selected() as usize).checked_sub(1) else {
+            return;
+        };
+        if let Some(preset) = preset_catalog().preset.get(preset_index) {
+            update_profile_field(&app_state_clone, |profile| {
+                preset.apply(profile);
+                Ok(())
+            });
+            app_state_clone.borrow().update_main_window_from_profile();
+        }
+    })
+}
+
+/// Connects the "Inherit From" dropdown signal handler
+fn connect_inherits_dropdown_handler(
+    dropdown: &DropDown,
+    app_state: &Rc<RefCell<AppState>>,
+) -> glib::SignalHandlerId {
+    let app_state_clone = app_state.clone();
+    dropdown.connect_selected_notify(move |dropdown| {
+        let mut state = app_state_clone.borrow_mut();
+        let Some(index) = state.selected_profile_index else {
+            return;
+        };
+        // Position 0 is "Nothing"
+        let base = (dropdown.selected() as usize).checked_sub(1)
+            .and_then(|position| state.inherit_choices().get(position).cloned());
+        let result = state.set_profile_base(index, base);
+        let main_window = state.main_window.clone();
+        drop(state);
+
+        if let Err(e) = result {
+            crate::profile_manager::show_profile_error(&main_window, &e);
+        }
+        app_state_clone.borrow().update_main_window_from_profile();
     })
 }
 
-/// Connects flow scale entry signal handler
-fn connect_flow_scale_entry_handler(
+/// Connects a "reset to inherited" button for one field
+fn connect_reset_button_handler(
+    button: &gtk::Button,
+    field: ProfileField,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    let app_state_clone = app_state.clone();
+    button.connect_clicked(move |_| {
+        let mut state = app_state_clone.borrow_mut();
+        if let Some(index) = state.selected_profile_index {
+            state.reset_field_to_inherited(index, field);
+        }
+        drop(state);
+        app_state_clone.borrow().update_main_window_from_profile();
+    });
+}
+
+/// Connects the executable patterns entry; patterns live in the metadata file only
+fn connect_patterns_entry_handler(
     entry: &Entry,
     app_state: &Rc<RefCell<AppState>>,
 ) -> glib::SignalHandlerId {
     let app_state_clone = app_state.clone();
     entry.connect_changed(move |entry| {
-        update_profile_field(&app_state_clone, |profile| {
-            if let Ok(value) = entry.text().parse::<f32>() {
-                profile.flow_scale = round_to_2_decimals(value);
-            }
-            Ok(())
-        });
+        let mut state = app_state_clone.borrow_mut();
+        let Some(exe) = state.selected_profile_index
+            .and_then(|index| state.config.game.get(index))
+            .map(|profile| profile.exe.clone()) else {
+            return;
+        };
+        state.profile_meta.entry(&exe).patterns = parse_patterns(&entry.text());
+        drop(state);
+        app_state_clone.borrow().refresh_match_tester();
     })
 }
 
-/// Connects performance mode switch signal handler
-fn connect_performance_mode_switch_handler(
+/// Connects the favorite switch; favorites get a star in the sidebar
+fn connect_favorite_switch_handler(
     switch: &Switch,
     app_state: &Rc<RefCell<AppState>>,
 ) -> glib::SignalHandlerId {
     let app_state_clone = app_state.clone();
-    switch.connect_state_set(move |_sw, active| {
-        update_profile_field(&app_state_clone, |profile| {
-            profile.performance_mode = active;
-            Ok(())
-        });
-        glib::Propagation::Proceed
+    switch.connect_active_notify(move |switch| {
+        let mut state = app_state_clone.borrow_mut();
+        let Some(exe) = state.selected_profile_index
+            .and_then(|index| state.config.game.get(index))
+            .map(|profile| profile.exe.clone()) else {
+            return;
+        };
+        state.profile_meta.entry(&exe).favorite = switch.is_active();
+        state.populate_sidebar_with_handlers(Some(app_state_clone.clone()));
     })
 }
 
-/// Connects HDR mode switch signal handler
-fn connect_hdr_mode_switch_handler(
-    switch: &Switch,
+/// Connects a group or tags entry. Edits are stored as typed, but the sidebar
+/// is only re-arranged on Enter or when focus leaves, so rows do not jump around
+/// while typing.
+fn connect_organization_entry_handler(
+    entry: &Entry,
     app_state: &Rc<RefCell<AppState>>,
+    store: fn(&mut crate::profile_meta::ProfileMeta, &str),
 ) -> glib::SignalHandlerId {
     let app_state_clone = app_state.clone();
-    switch.connect_state_set(move |_sw, active| {
-        update_profile_field(&app_state_clone, |profile| {
-            profile.hdr_mode = active;
-            Ok(())
+    let refresh_sidebar = move || {
+        let state = app_state_clone.borrow();
+        state.refresh_tag_filters(&app_state_clone);
+        // Keep the edited profile visible if it moved into a collapsed group
+        if let Some(group) = state.selected_profile_index
+            .and_then(|index| state.config.game.get(index))
+            .and_then(|profile| crate::sidebar::profile_group(profile, &state.profile_meta)) {
+            state.collapsed_groups.borrow_mut().remove(group);
+        }
+        state.apply_sidebar_view();
+    };
+    let refresh_on_activate = refresh_sidebar.clone();
+    entry.connect_activate(move |_| refresh_on_activate());
+    // Focus can also leave while the app state is borrowed, e.g. when the row is hidden
+    let focus_controller = gtk::EventControllerFocus::new();
+    focus_controller.connect_leave(move |_| {
+        let refresh_sidebar = refresh_sidebar.clone();
+        glib::idle_add_local(move || {
+            refresh_sidebar();
+            glib::ControlFlow::Break
         });
-        glib::Propagation::Proceed
+    });
+    entry.add_controller(focus_controller);
+
+    let app_state_clone = app_state.clone();
+    entry.connect_changed(move |entry| {
+        let mut state = app_state_clone.borrow_mut();
+        let Some(exe) = state.selected_profile_index
+            .and_then(|index| state.config.game.get(index))
+            .map(|profile| profile.exe.clone()) else {
+            return;
+        };
+        store(state.profile_meta.entry(&exe), &entry.text());
     })
 }
 
-/// Connects experimental present mode dropdown signal handler
-fn connect_experimental_present_mode_dropdown_handler(
-    dropdown: &DropDown,
+/// Connects the notes text view; notes are searchable from the sidebar
+fn connect_notes_buffer_handler(
+    buffer: &gtk::TextBuffer,
     app_state: &Rc<RefCell<AppState>>,
 ) -> glib::SignalHandlerId {
     let app_state_clone = app_state.clone();
-    dropdown.connect_selected_item_notify(move |dropdown| {
+    buffer.connect_changed(move |buffer| {
+        let mut state = app_state_clone.borrow_mut();
+        let Some(exe) = state.selected_profile_index
+            .and_then(|index| state.config.game.get(index))
+            .map(|profile| profile.exe.clone()) else {
+            return;
+        };
+        let (start, end) = buffer.bounds();
+        state.profile_meta.entry(&exe).notes = buffer.text(&start, &end, false).to_string();
+    })
+}
+
+/// Connects the button that expands the selected profile's patterns into concrete entries
+pub fn connect_expand_patterns_button_handler(
+    button: &gtk::Button,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    let app_state_clone = app_state.clone();
+    button.connect_clicked(move |_| {
+        // Candidates: running Vulkan processes plus whatever is typed into the tester
+        let mut candidates: Vec<String> = get_vulkan_processes().into_iter().map(|process| process.name).collect();
+        let tester_text = app_state_clone.borrow().match_tester_entry.text().trim().to_string();
+        if !tester_text.is_empty() {
+            candidates.push(tester_text);
+        }
+
+        let mut state = app_state_clone.borrow_mut();
+        let Some(index) = state.selected_profile_index else {
+            return;
+        };
+        let state = &mut *state;
+        let added = expand_patterns(&mut state.config, &mut state.profile_meta, index, &candidates);
+        let message = if added.is_empty() {
+            "No new matches".to_string()
+        } else {
+            state.save_current_config();
+            state.populate_sidebar_with_handlers(Some(app_state_clone.clone()));
+            format!("Added {} entr{}", added.len(), if added.len() == 1 { "y" } else { "ies" })
+        };
+        crate::ui_components::FeedbackUtils::show_temporary_feedback(&state.main_settings_box, &message, 2);
+    });
+}
+
+/// Connects the "which profile matches?" tester entry and its process picker
+pub fn connect_match_tester_handlers(
+    entry: &Entry,
+    pick_button: &gtk::Button,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    let app_state_clone = app_state.clone();
+    entry.connect_changed(move |_| {
+        app_state_clone.borrow().refresh_match_tester();
+    });
+
+    let app_state_clone = app_state.clone();
+    let entry_clone = entry.clone();
+    pick_button.connect_clicked(move |_| {
+        let main_window = app_state_clone.borrow().main_window.clone();
+        crate::profile_manager::show_process_picker_with_parent(&entry_clone, &main_window);
+    });
+}
+
+/// Connects the launch group's "Edit…" button; "Launch" runs `win.launch-profile`
+pub fn connect_launch_button_handler(
+    settings_button: &gtk::Button,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    let app_state_clone = app_state.clone();
+    settings_button.connect_clicked(move |_| {
+        let selected = app_state_clone.borrow().selected_profile_index;
+        if let Some(index) = selected {
+            crate::launch_window::show_launch_settings_dialog(app_state_clone.clone(), index);
+        }
+    });
+}
+
+/// Connects the button that opens the gamescope settings of the selected profile
+pub fn connect_gamescope_button_handler(button: &gtk::Button, app_state: &Rc<RefCell<AppState>>) {
+    let app_state_clone = app_state.clone();
+    button.connect_clicked(move |_| {
+        let selected = app_state_clone.borrow().selected_profile_index;
+        if let Some(index) = selected {
+            crate::launch_window::show_gamescope_settings_dialog(app_state_clone.clone(), index);
+        }
+    });
+}
+
+/// Connects the frame rate calculator inputs, refresh rate detection and the apply button
+pub fn connect_frame_calculator_handlers(detect_button: &gtk::Button, app_state: &Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    let base_fps_spin = state.calc_base_fps_spin.clone();
+    let refresh_spin = state.calc_refresh_spin.clone();
+    let apply_button = state.calc_apply_button.clone();
+    let main_window = state.main_window.clone();
+    drop(state);
+
+    for spin in [&base_fps_spin, &refresh_spin] {
+        let app_state_clone = app_state.clone();
+        spin.connect_value_changed(move |_| {
+            app_state_clone.borrow().refresh_frame_calculator();
+        });
+    }
+
+    let refresh_spin_clone = refresh_spin.clone();
+    let main_window_clone = main_window.clone();
+    detect_button.connect_clicked(move |_| {
+        match detect_refresh_rate(&main_window_clone) {
+            Some(refresh_rate) => refresh_spin_clone.set_value(refresh_rate.round()),
+            None => eprintln!("Could not detect the monitor refresh rate"),
+        }
+    });
+
+    let app_state_clone = app_state.clone();
+    apply_button.connect_clicked(move |_| {
+        let mode = app_state_clone.borrow().present_mode_suggestion().0;
         update_profile_field(&app_state_clone, |profile| {
-            let selected_text = dropdown.selected_item()
-                .and_then(|item| item.downcast_ref::<StringObject>().map(|s| s.string().to_string()));
-            if let Some(text) = selected_text {
-                profile.experimental_present_mode = text;
-            }
+            profile.experimental_present_mode = mode.to_string();
             Ok(())
         });
+        app_state_clone.borrow().update_main_window_from_profile();
+    });
+
+    // Start from the monitor's refresh rate when GDK knows it
+    if let Some(refresh_rate) = detect_refresh_rate(&main_window) {
+        refresh_spin.set_value(refresh_rate.round());
+    }
+}
+
+/// Connects the buttons that create, remove and list desktop launchers
+pub fn connect_launcher_button_handlers(
+    create_button: &gtk::Button,
+    remove_button: &gtk::Button,
+    list_button: &gtk::Button,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    let app_state_clone = app_state.clone();
+    create_button.connect_clicked(move |_| {
+        let selected = app_state_clone.borrow().selected_profile_index;
+        if let Some(index) = selected {
+            crate::launch_window::show_create_launcher_dialog(app_state_clone.clone(), index);
+        }
+    });
+
+    let app_state_clone = app_state.clone();
+    remove_button.connect_clicked(move |_| {
+        let selected = app_state_clone.borrow().selected_profile_index;
+        if let Some(index) = selected {
+            crate::launch_window::remove_profile_launcher(&app_state_clone, index);
+        }
+    });
+
+    let app_state_clone = app_state.clone();
+    list_button.connect_clicked(move |_| {
+        crate::launch_window::show_launchers_dialog(app_state_clone.clone());
+    });
+}
+
+/// Connects the Steam AppID entry; the id is stored in the metadata file only
+fn connect_steam_app_id_entry_handler(
+    entry: &Entry,
+    app_state: &Rc<RefCell<AppState>>,
+) -> glib::SignalHandlerId {
+    let app_state_clone = app_state.clone();
+    entry.connect_changed(move |entry| {
+        let mut state = app_state_clone.borrow_mut();
+        let Some(exe) = state.selected_profile_index
+            .and_then(|index| state.config.game.get(index))
+            .map(|profile| profile.exe.clone()) else {
+            return;
+        };
+        let app_id = entry.text().trim().to_string();
+        state.profile_meta.entry(&exe).steam_app_id = (!app_id.is_empty()).then_some(app_id);
+        drop(state);
+        app_state_clone.borrow().refresh_steam_launch_options();
     })
 }
 
-/// Connects save button handler
-pub fn connect_save_button_handler(
-    save_button: &gtk::Button,
+/// Connects the copy and "Write to Steam…" buttons of the Steam group
+pub fn connect_steam_button_handlers(
+    copy_button: &gtk::Button,
+    write_button: &gtk::Button,
     app_state: &Rc<RefCell<AppState>>,
 ) {
     let app_state_clone = app_state.clone();
-    save_button.connect_clicked(move |_| {
-        handle_save_button_click(&app_state_clone);
+    copy_button.connect_clicked(move |button| {
+        let state = app_state_clone.borrow();
+        if let Some(launch_options) = state.steam_launch_options_row.subtitle() {
+            button.clipboard().set_text(&launch_options);
+            crate::ui_components::FeedbackUtils::show_temporary_feedback(&state.main_settings_box, "Copied!", 2);
+        }
+    });
+
+    let app_state_clone = app_state.clone();
+    write_button.connect_clicked(move |_| {
+        let selected = app_state_clone.borrow().selected_profile_index;
+        if let Some(index) = selected {
+            crate::launch_window::show_write_steam_launch_options_dialog(app_state_clone.clone(), index);
+        }
     });
 }
 
-/// Handles save button click logic
-fn handle_save_button_click(app_state: &Rc<RefCell<AppState>>) {
+/// Handles `win.save`
+/// Writes the editor values to the selected profile and saves the config
+pub fn handle_save(app_state: &Rc<RefCell<AppState>>) {
     let state_ref = app_state.borrow();
     if let Some(index) = state_ref.selected_profile_index {
         // Get current UI values
-        let multiplier_str = state_ref.multiplier_dropdown.selected_item()
-            .and_then(|item| item.downcast_ref::<StringObject>().map(|s| s.string().to_string()));
-        let flow_scale_text = state_ref.flow_scale_entry.text().to_string();
-        let performance_mode_active = state_ref.performance_mode_switch.is_active();
-        let hdr_mode_active = state_ref.hdr_mode_switch.is_active();
-        let exp_mode_str = state_ref.experimental_present_mode_dropdown.selected_item()
-            .and_then(|item| item.downcast_ref::<StringObject>().map(|s| s.string().to_string()));
-        
+        let values: Vec<_> = state_ref.field_editors.iter()
+            .filter_map(|editor| match editor.widget.read() {
+                Some(EditorInput::Value(value)) => Some((editor.field, value)),
+                _ => None,
+            })
+            .collect();
+
         let main_settings_box = state_ref.main_settings_box.clone();
         drop(state_ref);
 
         // Update profile with current values
         let mut state = app_state.borrow_mut();
         if let Some(profile) = state.config.game.get_mut(index) {
-            if let Some(text) = multiplier_str {
-                profile.multiplier = if text == "off" { 1 } else { text.parse().unwrap_or(1) };
-            }
-
-            if let Ok(value) = flow_scale_text.parse::<f32>() {
-                profile.flow_scale = round_to_2_decimals(value);
-            }
-
-            profile.performance_mode = performance_mode_active;
-            profile.hdr_mode = hdr_mode_active;
-
-            if let Some(text) = exp_mode_str {
-                profile.experimental_present_mode = text;
+            let before = profile.clone();
+            for (field, value) in values {
+                if let Err(e) = field.set_value(profile, value) {
+                    eprintln!("Error saving {}: {}", field.key(), e);
+                }
             }
 
+            state.apply_profile_edit(index, &before);
             state.save_current_config();
             
             // Show feedback
@@ -186,52 +465,130 @@ fn handle_save_button_click(app_state: &Rc<RefCell<AppState>>) {
     }
 }
 
-/// Connects sidebar list box row activation handler
-pub fn connect_sidebar_row_activated_handler(
+/// Connects sidebar list box selection handler; selecting a single row by mouse
+/// or arrow keys shows its profile, selecting several offers the bulk actions
+pub fn connect_sidebar_selection_handler(
     sidebar_list_box: &gtk::ListBox,
     app_state: &Rc<RefCell<AppState>>,
 ) {
     let app_state_clone = app_state.clone();
-    sidebar_list_box.connect_row_activated(move |_list_box, row| {
-        let index = row.index() as usize;
-        let mut state = app_state_clone.borrow_mut();
-        state.selected_profile_index = Some(index);
-        drop(state);
-
+    sidebar_list_box.connect_selected_rows_changed(move |_list_box| {
+        // Selection also changes while the app state is borrowed, so defer the update
         let app_state_for_idle = app_state_clone.clone();
         glib::idle_add_local(move || {
+            let mut state = app_state_for_idle.borrow_mut();
+            state.refresh_bulk_actions();
+            // Rows are deselected while the list is re-arranged
+            let selected = state.selected_profile_indices();
+            let [index] = selected[..] else {
+                return glib::ControlFlow::Break;
+            };
+            if state.selected_profile_index == Some(index) {
+                return glib::ControlFlow::Break;
+            }
+            state.selected_profile_index = Some(index);
+            drop(state);
             app_state_for_idle.borrow().update_main_window_from_profile();
             glib::ControlFlow::Break
         });
     });
 }
 
-/// Connects create profile button handler
-pub fn connect_create_profile_button_handler(
-    create_profile_button: &gtk::Button,
+/// Connects activation of sidebar rows: a group header collapses or expands
+/// the group, a profile opens its settings in the narrow layout
+pub fn connect_sidebar_row_activated_handler(
+    sidebar_list_box: &gtk::ListBox,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    let app_state_clone = app_state.clone();
+    sidebar_list_box.connect_row_activated(move |_list_box, row| {
+        let state = app_state_clone.borrow();
+        match state.group_of_header_row(row) {
+            Some(group) => {
+                state.toggle_group_collapsed(&group);
+                row.grab_focus();
+            }
+            None => state.show_profile_pane(),
+        }
+    });
+}
+
+/// Connects Alt+Up / Alt+Down in the sidebar, which move the selected profile
+pub fn connect_sidebar_reorder_handler(
+    sidebar_list_box: &gtk::ListBox,
     app_state: &Rc<RefCell<AppState>>,
 ) {
     let app_state_clone = app_state.clone();
-    create_profile_button.connect_clicked(move |_| {
-        crate::profile_manager::show_create_profile_dialog(app_state_clone.clone());
+    let key_controller = gtk::EventControllerKey::new();
+    // Runs before the list box's own arrow key bindings
+    key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    key_controller.connect_key_pressed(move |_, key, _, modifiers| {
+        if !modifiers.contains(gtk::gdk::ModifierType::ALT_MASK) {
+            return glib::Propagation::Proceed;
+        }
+        let offset = match key {
+            gtk::gdk::Key::Up => -1,
+            gtk::gdk::Key::Down => 1,
+            _ => return glib::Propagation::Proceed,
+        };
+        crate::profile_manager::move_selected_profile(&app_state_clone, offset);
+        glib::Propagation::Stop
     });
+    sidebar_list_box.add_controller(key_controller);
 }
 
-/// Generic function to update dropdown selection by text value
-pub fn update_dropdown_selection(dropdown: &DropDown, target_value: &str) -> bool {
-    if let Some(model) = dropdown.model() {
-        if let Some(list_model) = model.downcast_ref::<gtk::StringList>() {
-            for i in 0..list_model.n_items() {
-                if let Some(string_value) = list_model.string(i) {
-                    if string_value.as_str() == target_value {
-                        dropdown.set_selected(i);
-                        return true;
-                    }
-                }
+/// Connects the sidebar search entry, sort dropdown and filter chips
+pub fn connect_sidebar_search_handlers(app_state: &Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+
+    let app_state_clone = app_state.clone();
+    state.sidebar_search_entry.connect_search_changed(move |_| {
+        app_state_clone.borrow().apply_sidebar_view();
+    });
+    let app_state_clone = app_state.clone();
+    state.sidebar_sort_dropdown.connect_selected_notify(move |_| {
+        app_state_clone.borrow().apply_sidebar_view();
+    });
+    for (_, button) in &state.filter_chip_buttons {
+        let app_state_clone = app_state.clone();
+        button.connect_toggled(move |_| {
+            app_state_clone.borrow().apply_sidebar_view();
+        });
+    }
+
+    // Enter opens the best match, Down moves into the list and Escape clears the search
+    let list_box = state.sidebar_list_box.clone();
+    state.sidebar_search_entry.connect_activate(move |_| {
+        let first_profile = (0..)
+            .map_while(|position| list_box.row_at_index(position))
+            .find(|row| row.is_selectable());
+        if let Some(row) = first_profile {
+            list_box.unselect_all();
+            list_box.select_row(Some(&row));
+            row.grab_focus();
+        }
+    });
+    let list_box = state.sidebar_list_box.clone();
+    let key_controller = gtk::EventControllerKey::new();
+    key_controller.connect_key_pressed(move |_, key, _, _| {
+        if key != gtk::gdk::Key::Down {
+            return glib::Propagation::Proceed;
+        }
+        match list_box.selected_row().or_else(|| list_box.row_at_index(0)) {
+            Some(row) => {
+                row.grab_focus();
+                glib::Propagation::Stop
             }
+            None => glib::Propagation::Proceed,
         }
-    }
-    false
+    });
+    state.sidebar_search_entry.add_controller(key_controller);
+    state.sidebar_search_entry.connect_stop_search(|entry| {
+        entry.set_text("");
+    });
+
+    // Typing anywhere in the window starts a search
+    state.sidebar_search_entry.set_key_capture_widget(Some(&state.main_window));
 }
 
 /// Blocks all profile-related signal handlers temporarily
@@ -239,27 +596,106 @@ pub fn with_blocked_signals<F, R>(app_state: &AppState, f: F) -> R
 where
     F: FnOnce() -> R,
 {
+    let notes_buffer = app_state.notes_view.buffer();
+    let editor_handlers = app_state.field_editors.iter()
+        .map(|editor| (editor.widget.object(), editor.handler_id.as_ref()));
+    let handlers: Vec<(&glib::Object, &glib::SignalHandlerId)> = [
+        (app_state.preset_dropdown.upcast_ref::<glib::Object>(), app_state.preset_dropdown_handler_id.as_ref()),
+        (app_state.inherits_dropdown.upcast_ref(), app_state.inherits_dropdown_handler_id.as_ref()),
+        (app_state.patterns_entry.upcast_ref(), app_state.patterns_entry_handler_id.as_ref()),
+        (app_state.favorite_switch.upcast_ref(), app_state.favorite_switch_handler_id.as_ref()),
+        (app_state.group_entry.upcast_ref(), app_state.group_entry_handler_id.as_ref()),
+        (app_state.tags_entry.upcast_ref(), app_state.tags_entry_handler_id.as_ref()),
+        (notes_buffer.upcast_ref(), app_state.notes_buffer_handler_id.as_ref()),
+        (app_state.steam_app_id_entry.upcast_ref(), app_state.steam_app_id_entry_handler_id.as_ref()),
+    ]
+    .into_iter()
+    .chain(editor_handlers)
+    .filter_map(|(object, id)| id.map(|id| (object, id)))
+    .collect();
+
     // Block signals
-    let mult_guard = app_state.multiplier_dropdown_handler_id.as_ref()
-        .map(|id| app_state.multiplier_dropdown.block_signal(id));
-    let flow_guard = app_state.flow_scale_entry_handler_id.as_ref()
-        .map(|id| app_state.flow_scale_entry.block_signal(id));
-    let perf_guard = app_state.performance_mode_switch_handler_id.as_ref()
-        .map(|id| app_state.performance_mode_switch.block_signal(id));
-    let hdr_guard = app_state.hdr_mode_switch_handler_id.as_ref()
-        .map(|id| app_state.hdr_mode_switch.block_signal(id));
-    let exp_guard = app_state.experimental_present_mode_dropdown_handler_id.as_ref()
-        .map(|id| app_state.experimental_present_mode_dropdown.block_signal(id));
+    for (object, id) in &handlers {
+        object.block_signal(id);
+    }
 
     // Execute the function
     let result = f();
 
-    // Signals are automatically unblocked when guards go out of scope
-    let _ = mult_guard;
-    let _ = flow_guard;
-    let _ = perf_guard;
-    let _ = hdr_guard;
-    let _ = exp_guard;
+    // Unblock signals again so later user edits reach the profile
+    for (object, id) in &handlers {
+        object.unblock_signal(id);
+    }
 
     result
-}
\ No newline at end of file
+}
+
+/// Connects dragging the sidebar edge, which fixes the sidebar at the dragged width
+pub fn connect_sidebar_resize_handler(
+    handle: &gtk::Box,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    handle.set_cursor_from_name(Some("col-resize"));
+    let start_width = Rc::new(Cell::new(0));
+    let drag = gtk::GestureDrag::new();
+
+    let start_width_clone = start_width.clone();
+    drag.connect_drag_begin(move |gesture, _, _| {
+        // The handle is laid over the whole sidebar, so its parent has the sidebar's width
+        let width = gesture.widget().parent().map_or(0, |sidebar| sidebar.width());
+        start_width_clone.set(width);
+    });
+
+    let app_state_clone = app_state.clone();
+    drag.connect_drag_update(move |_, offset_x, _| {
+        app_state_clone.borrow().set_sidebar_width(start_width.get() + offset_x as i32);
+    });
+    handle.add_controller(drag);
+}
+
+/// Connects closing the main window, which saves the window and sidebar state for the next launch
+pub fn connect_main_window_close_handler(
+    main_window: &libadwaita::ApplicationWindow,
+    app_state: &Rc<RefCell<AppState>>,
+) {
+    let app_state_clone = app_state.clone();
+    main_window.connect_close_request(move |_| {
+        match app_state_clone.try_borrow() {
+            Ok(state) => {
+                if let Err(e) = crate::ui_state::save_ui_state(&state.ui_state()) {
+                    eprintln!("Error saving UI state: {}", e);
+                }
+            }
+            Err(_) => eprintln!("UI state not saved: application state busy"),
+        }
+        glib::Propagation::Proceed
+    });
+}
+
+/// Connects the split view collapsing on narrow windows: the resize handle is
+/// hidden and targets grow to finger size, as they do on touchscreens
+pub fn connect_adaptive_layout_handler(
+    split_view: &libadwaita::NavigationSplitView,
+    resize_handle: &gtk::Box,
+    main_window: &libadwaita::ApplicationWindow,
+) {
+    let has_touchscreen = WidgetExt::display(main_window)
+        .default_seat()
+        .is_some_and(|seat| seat.capabilities().contains(gtk::gdk::SeatCapabilities::TOUCH));
+
+    let update = {
+        let resize_handle = resize_handle.clone();
+        let main_window = main_window.clone();
+        move |split_view: &libadwaita::NavigationSplitView| {
+            let collapsed = split_view.is_collapsed();
+            resize_handle.set_visible(!collapsed);
+            if collapsed || has_touchscreen {
+                main_window.add_css_class("touch-targets");
+            } else {
+                main_window.remove_css_class("touch-targets");
+            }
+        }
+    };
+    update(split_view);
+    split_view.connect_collapsed_notify(update);
+}
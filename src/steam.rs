@@ -0,0 +1,172 @@
+//! Steam integration
+//! Generates launch options for Steam games and writes them into the
+//! per-user `localconfig.vdf`, keeping a backup of the previous file.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::launcher::{build_launch_environment, prefixed_command, shell_quote, LaunchSettings};
+use crate::vdf::{parse_vdf, write_vdf, VdfNode};
+
+/// Builds the launch option string Steam expects, e.g. `ENABLE_LSFG=1 LSFG_PROCESS=game.exe %command%`
+pub fn generate_launch_options(profile_exe: &str, settings: Option<&LaunchSettings>, prefix: &[String]) -> String {
+    let default_settings = LaunchSettings::default();
+    let env = build_launch_environment(profile_exe, settings.unwrap_or(&default_settings));
+    let mut parts: Vec<String> = env.iter()
+        .map(|(key, value)| format!("{}={}", key, shell_quote(value)))
+        .collect();
+    parts.push(prefixed_command(prefix, "%command%"));
+    parts.join(" ")
+}
+
+/// Returns true if an app id looks like one Steam would use
+pub fn is_valid_app_id(app_id: &str) -> bool {
+    !app_id.is_empty() && app_id.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Locates the Steam installation (native, legacy symlink or Flatpak)
+pub fn find_steam_root() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    [
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        home.join(".var/app/com.valvesoftware.Steam/data/Steam"),
+    ]
+    .into_iter()
+    .find(|path| path.join("userdata").is_dir())
+}
+
+/// Picks the localconfig.vdf of the most recently active Steam user
+pub fn find_localconfig(steam_root: &Path) -> Option<PathBuf> {
+    fs::read_dir(steam_root.join("userdata")).ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("config").join("localconfig.vdf"))
+        .filter(|path| path.is_file())
+        .max_by_key(|path| path.metadata().and_then(|m| m.modified()).ok())
+}
+
+/// Steam rewrites localconfig.vdf on exit, so edits made while it runs get lost
+pub fn is_steam_running() -> bool {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return false;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        .any(|entry| {
+            fs::read_to_string(entry.path().join("comm"))
+                .map(|comm| comm.trim() == "steam")
+                .unwrap_or(false)
+        })
+}
+
+fn apps_node(root: &mut VdfNode) -> &mut VdfNode {
+    root.child_object_mut("Software")
+        .child_object_mut("Valve")
+        .child_object_mut("Steam")
+        .child_object_mut("apps")
+}
+
+/// Reads the launch options currently stored for an app, if any
+pub fn read_launch_options(localconfig: &Path, app_id: &str) -> Result<Option<String>, String> {
+    let contents = fs::read_to_string(localconfig)
+        .map_err(|e| format!("Failed to read {}: {}", localconfig.display(), e))?;
+    let nodes = parse_vdf(&contents)?;
+    Ok(nodes.first()
+        .and_then(|root| root.child("Software"))
+        .and_then(|node| node.child("Valve"))
+        .and_then(|node| node.child("Steam"))
+        .and_then(|node| node.child("apps"))
+        .and_then(|node| node.child(app_id))
+        .and_then(|node| node.child("LaunchOptions"))
+        .and_then(|node| node.string())
+        .map(str::to_string))
+}
+
+/// Writes launch options for an app into localconfig.vdf. The previous file is
+/// copied next to it first; the backup path is returned.
+pub fn write_launch_options(localconfig: &Path, app_id: &str, launch_options: &str) -> Result<PathBuf, String> {
+    if !is_valid_app_id(app_id) {
+        return Err(format!("'{}' is not a valid Steam AppID", app_id));
+    }
+
+    let contents = fs::read_to_string(localconfig)
+        .map_err(|e| format!("Failed to read {}: {}", localconfig.display(), e))?;
+    let mut nodes = parse_vdf(&contents)?;
+    let root = nodes.first_mut().ok_or("localconfig.vdf is empty")?;
+    apps_node(root).child_object_mut(app_id).set_string("LaunchOptions", launch_options);
+
+    let backup = localconfig.with_extension("vdf.lsfg-vk-ui.bak");
+    write_atomically(&backup, &contents).map_err(|e| format!("Failed to back up localconfig.vdf: {}", e))?;
+    write_atomically(localconfig, &write_vdf(&nodes))
+        .map_err(|e| format!("Failed to write {}: {}", localconfig.display(), e))?;
+    Ok(backup)
+}
+
+// Writes a temporary file next to `path` and renames it over, so Steam never
+// sees a half written file
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name"))?;
+    let temporary = path.with_file_name(format!(".{}.lsfg-vk-ui.tmp", file_name.to_string_lossy()));
+    let result = File::create(&temporary)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALCONFIG: &str = include_str!("../tests/fixtures/localconfig.vdf");
+    const LOCALCONFIG_EDITED: &str = include_str!("../tests/fixtures/localconfig_edited.vdf");
+
+    fn localconfig_copy(dir: &Path) -> PathBuf {
+        let path = dir.join("localconfig.vdf");
+        fs::write(&path, LOCALCONFIG).unwrap();
+        path
+    }
+
+    #[test]
+    fn write_launch_options_edits_only_the_app() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = localconfig_copy(dir.path());
+
+        let backup = write_launch_options(&path, "570", "ENABLE_LSFG=1 LSFG_PROCESS=dota2 %command%").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), LOCALCONFIG_EDITED);
+        assert_eq!(fs::read_to_string(backup).unwrap(), LOCALCONFIG);
+        // Only the two files are left; the temporary one was renamed away
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn write_launch_options_adds_missing_apps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = localconfig_copy(dir.path());
+
+        write_launch_options(&path, "1245620", "%command% -windowed").unwrap();
+        write_launch_options(&path, "292030", "ENABLE_LSFG=1 %command%").unwrap();
+
+        assert_eq!(read_launch_options(&path, "1245620").unwrap().as_deref(), Some("%command% -windowed"));
+        assert_eq!(read_launch_options(&path, "292030").unwrap().as_deref(), Some("ENABLE_LSFG=1 %command%"));
+        assert_eq!(read_launch_options(&path, "570").unwrap().as_deref(), Some("-novid -console"));
+    }
+
+    #[test]
+    fn write_launch_options_rejects_invalid_app_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = localconfig_copy(dir.path());
+
+        assert!(write_launch_options(&path, "not-an-id", "%command%").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), LOCALCONFIG);
+    }
+}
@@ -28,7 +28,7 @@ pub fn setup_icon_theme() {
 /// Returns the application's CSS as a string
 fn get_application_css() -> String {
     format!(
-        "{}{}{}{}{}{}{}{}{}{}{}",
+        "{}{}{}{}{}{}{}{}{}{}{}{}{}",
         get_button_styles(),
         get_sidebar_styles(),
         get_profile_styles(),
@@ -39,10 +39,58 @@ fn get_application_css() -> String {
         get_libadwaita_enhancements(),
         get_toast_styles(),
         get_preferences_styles(),
-        get_icon_styles()
+        get_icon_styles(),
+        get_touch_styles(),
+        get_focus_styles()
     )
 }
 
+/// Finger-sized targets, used on touchscreens and in the narrow layout
+fn get_touch_styles() -> &'static str {
+    r#"
+        window.touch-targets button,
+        window.touch-targets entry,
+        window.touch-targets dropdown > button {
+            min-height: 44px;
+        }
+
+        window.touch-targets button.circular,
+        window.touch-targets button.flat {
+            min-width: 44px;
+        }
+
+        window.touch-targets .navigation-sidebar > row {
+            min-height: 48px;
+        }
+
+        window.touch-targets .filter-chip {
+            min-height: 36px;
+            padding-left: 14px;
+            padding-right: 14px;
+        }
+
+        window.touch-targets switch {
+            min-height: 30px;
+            min-width: 52px;
+        }
+    "#
+}
+
+/// A focus ring that reads from the couch, shown once a gamepad has been used
+fn get_focus_styles() -> &'static str {
+    r#"
+        window.gamepad-navigation *:focus-visible {
+            outline: 3px solid @accent_color;
+            outline-offset: 2px;
+        }
+
+        window.gamepad-navigation row:focus-visible {
+            background-color: alpha(@accent_color, 0.2);
+            outline-offset: -3px;
+        }
+    "#
+}
+
 /// Button-related styles
 fn get_button_styles() -> &'static str {
     r#"
@@ -100,6 +148,30 @@ fn get_profile_styles() -> &'static str {
             color: @theme_selected_fg_color;
         }
 
+        .match-highlight {
+            background-color: alpha(@accent_bg_color, 0.25);
+            border-radius: 6px;
+        }
+
+        .reorderable-row:drop(active) {
+            box-shadow: inset 0 2px @accent_color;
+        }
+
+        .filter-chip {
+            border-radius: 999px;
+            padding: 2px 10px;
+            font-size: 0.9em;
+        }
+
+        .sidebar-group-header {
+            margin-top: 6px;
+            opacity: 0.8;
+        }
+
+        .sidebar-resize-handle:hover {
+            background-color: alpha(currentColor, 0.1);
+        }
+
         .profile-actions {
             opacity: 0.7;
             transition: opacity 200ms ease;
@@ -124,6 +196,40 @@ fn get_settings_styles() -> &'static str {
         .settings-row {
             padding: 8px 0;
         }
+
+        .inherited-value {
+            opacity: 0.55;
+        }
+
+        .mixed-value {
+            opacity: 0.55;
+        }
+
+        .preset-badge {
+            font-size: 0.85em;
+            font-weight: bold;
+            padding: 2px 8px;
+            border-radius: 999px;
+        }
+
+        .preset-badge-matched {
+            color: @success_color;
+            background-color: alpha(@success_color, 0.15);
+        }
+
+        .preset-badge-custom {
+            color: @warning_color;
+            background-color: alpha(@warning_color, 0.15);
+        }
+
+        .compare-cell {
+            padding: 4px 8px;
+            border-radius: 6px;
+        }
+
+        .compare-diff {
+            background-color: alpha(@warning_color, 0.15);
+        }
     "#
 }
 
@@ -287,4 +393,6 @@ pub mod css_classes {
     pub const PROFILE_ACTIONS: &[&str] = &["profile-actions"];
     pub const SETTINGS_GROUP: &[&str] = &["settings-group"];
     pub const PROCESS_LIST_ITEM: &[&str] = &["process-list-item"];
+    pub const PRESET_BADGE_MATCHED: &[&str] = &["preset-badge", "preset-badge-matched"];
+    pub const PRESET_BADGE_CUSTOM: &[&str] = &["preset-badge", "preset-badge-custom"];
 }
\ No newline at end of file
@@ -0,0 +1,162 @@
+//! User-defined profile templates
+//! Templates are stored next to the lsfg-vk config in `templates.toml` so they
+//! never end up in the file lsfg-vk itself reads.
+
+use serde::{Deserialize, Serialize};
+use std::{fs, io};
+use std::path::PathBuf;
+
+use crate::config::{get_config_dir, Config, GameProfile};
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct TemplateFile {
+    #[serde(default)]
+    pub template: Vec<ProfileTemplate>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct ProfileTemplate {
+    pub name: String,
+    /// Stored inline like a `[[game]]` entry; the exe is always empty and left out
+    #[serde(flatten, with = "settings_without_exe")]
+    pub settings: GameProfile,
+}
+
+// Goes through a table so the field list stays on `GameProfile` alone
+mod settings_without_exe {
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::config::GameProfile;
+
+    pub fn serialize<S: Serializer>(profile: &GameProfile, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut table = toml::Table::try_from(profile).map_err(ser::Error::custom)?;
+        table.remove("exe");
+        table.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameProfile, D::Error> {
+        let mut profile: GameProfile = toml::Table::deserialize(deserializer)?
+            .try_into()
+            .map_err(de::Error::custom)?;
+        profile.exe.clear();
+        Ok(profile)
+    }
+}
+
+/// Where a new profile takes its initial settings from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileSource {
+    Defaults,
+    Profile(usize),
+    Template(usize),
+}
+
+impl ProfileSource {
+    /// Label shown in the "Based on" dropdown
+    pub fn label(&self, config: &Config, templates: &TemplateFile) -> String {
+        match self {
+            ProfileSource::Defaults => "Defaults".to_string(),
+            ProfileSource::Profile(index) => format!("Profile: {}", config.game[*index].exe),
+            ProfileSource::Template(index) => format!("Template: {}", templates.template[*index].name),
+        }
+    }
+
+    /// Resolves the settings the new profile starts from
+    pub fn resolve(&self, config: &Config, templates: &TemplateFile) -> GameProfile {
+        match self {
+            ProfileSource::Defaults => GameProfile::default(),
+            ProfileSource::Profile(index) => config.game.get(*index).cloned().unwrap_or_default(),
+            ProfileSource::Template(index) => templates.template.get(*index)
+                .map(|t| t.settings.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Lists every available source: defaults first, then profiles, then templates
+pub fn list_profile_sources(config: &Config, templates: &TemplateFile) -> Vec<ProfileSource> {
+    let mut sources = vec![ProfileSource::Defaults];
+    sources.extend((0..config.game.len()).map(ProfileSource::Profile));
+    sources.extend((0..templates.template.len()).map(ProfileSource::Template));
+    sources
+}
+
+// --- Template File Handling Functions ---
+
+pub fn get_templates_path() -> Result<PathBuf, io::Error> {
+    Ok(get_config_dir()?.join("templates.toml"))
+}
+
+pub fn load_templates() -> Result<TemplateFile, io::Error> {
+    let path = get_templates_path()?;
+    if !path.exists() {
+        return Ok(TemplateFile::default());
+    }
+    let contents = fs::read_to_string(&path)?;
+    toml::from_str(&contents).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("Failed to parse templates: {}", e))
+    })
+}
+
+pub fn save_templates(templates: &TemplateFile) -> Result<(), io::Error> {
+    let path = get_templates_path()?;
+    let toml_string = toml::to_string_pretty(templates)
+        .map_err(|e| io::Error::other(format!("Failed to serialize templates: {}", e)))?;
+    fs::write(&path, toml_string)?;
+    println!("Saved templates to: {:?}", path);
+    Ok(())
+}
+
+/// Stores the settings of a profile as a named template, replacing any template with the same name
+pub fn save_profile_as_template(name: &str, profile: &GameProfile) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+
+    let mut templates = load_templates().map_err(|e| e.to_string())?;
+    let settings = GameProfile { exe: String::new(), ..profile.clone() };
+
+    match templates.template.iter_mut().find(|t| t.name == name) {
+        Some(existing) => existing.settings = settings,
+        None => templates.template.push(ProfileTemplate { name: name.to_string(), settings }),
+    }
+
+    save_templates(&templates).map_err(|e| format!("Failed to save template: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_are_stored_without_an_exe() {
+        let templates = TemplateFile {
+            template: vec![ProfileTemplate {
+                name: "Shooters".to_string(),
+                settings: GameProfile { multiplier: 3, flow_scale: 0.85, gpu: Some("01:00.0".to_string()), ..GameProfile::default() },
+            }],
+        };
+
+        let text = toml::to_string_pretty(&templates).unwrap();
+        assert!(!text.contains("exe"));
+        assert!(text.contains("flow_scale = 0.85"));
+
+        let loaded: TemplateFile = toml::from_str(&text).unwrap();
+        let settings = &loaded.template[0].settings;
+        assert_eq!(loaded.template[0].name, "Shooters");
+        assert_eq!((settings.multiplier, settings.flow_scale), (3, 0.85));
+        assert_eq!(settings.gpu.as_deref(), Some("01:00.0"));
+    }
+
+    #[test]
+    fn missing_template_fields_take_profile_defaults() {
+        let loaded: TemplateFile = toml::from_str("[[template]]\nname = \"Quiet\"\nexe = \"stray.exe\"\nhdr_mode = true\n").unwrap();
+        let settings = &loaded.template[0].settings;
+        let defaults = GameProfile::default();
+        assert!(settings.hdr_mode);
+        assert!(settings.exe.is_empty());
+        assert_eq!(settings.multiplier, defaults.multiplier);
+        assert_eq!(settings.experimental_present_mode, defaults.experimental_present_mode);
+    }
+}
@@ -103,9 +103,12 @@ impl DialogFactory {
     {
         let dialog = Self::create_message_dialog(parent, title, message, gtk::MessageType::Question);
         
+        // Executable names: keep on-screen keyboards from autocorrecting them
         let entry = Entry::builder()
             .placeholder_text(placeholder)
             .hexpand(true)
+            .input_purpose(gtk::InputPurpose::FreeForm)
+            .input_hints(gtk::InputHints::NO_SPELLCHECK | gtk::InputHints::NO_EMOJI)
             .build();
         
         if let Some(value) = initial_value {
@@ -150,6 +153,46 @@ impl DialogFactory {
         
         (dialog, entry, browse_button)
     }
+
+    /// Creates a dialog with one labeled entry per field: (label, placeholder, initial value)
+    pub fn create_form_dialog(
+        parent: &ApplicationWindow,
+        title: &str,
+        message: &str,
+        fields: &[(&str, &str, Option<&str>)],
+    ) -> (MessageDialog, Vec<Entry>) {
+        let dialog = Self::create_message_dialog(parent, title, message, gtk::MessageType::Question);
+
+        let form_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .margin_top(12)
+            .margin_bottom(12)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+
+        let entries: Vec<Entry> = fields.iter().map(|(label, placeholder, initial_value)| {
+            let entry = Entry::builder()
+                .placeholder_text(*placeholder)
+                .hexpand(true)
+                .build();
+            if let Some(value) = initial_value {
+                entry.set_text(value);
+            }
+            let label = Label::builder()
+                .label(*label)
+                .halign(gtk::Align::Start)
+                .build();
+            form_box.append(&label);
+            form_box.append(&entry);
+            entry
+        }).collect();
+
+        dialog.content_area().append(&form_box);
+        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+        (dialog, entries)
+    }
 }
 
 /// Factory for creating process picker components
@@ -357,6 +400,20 @@ impl LayoutFactory {
             .build()
     }
 
+    /// Creates a horizontal row with a leading label and a trailing widget
+    pub fn create_labeled_row<W: IsA<gtk::Widget>>(label: &str, widget: &W) -> Box {
+        let row = Self::create_horizontal_box_with_margins(12);
+        let label = Label::builder()
+            .label(label)
+            .halign(gtk::Align::Start)
+            .hexpand(true)
+            .xalign(0.0)
+            .build();
+        row.append(&label);
+        row.append(widget);
+        row
+    }
+
     /// Creates a vertical box with spacing
     #[allow(dead_code)]
     #[allow(dead_code)]
@@ -0,0 +1,81 @@
+//! Window and session state
+//! How the window was left — its size, the sidebar width, the selected
+//! profile and the sidebar's sort and filters — is kept in `ui-state.toml`
+//! under the XDG state directory, apart from the config, and put back on the
+//! next launch.
+
+use serde::{Deserialize, Serialize};
+use std::{fs, io};
+use std::path::PathBuf;
+
+use crate::sidebar::{FilterChip, SortMode};
+
+/// Range the sidebar can be dragged within, in pixels
+pub const MIN_SIDEBAR_WIDTH: i32 = 180;
+pub const MAX_SIDEBAR_WIDTH: i32 = 600;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UiState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_width: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_height: Option<i32>,
+    #[serde(default)]
+    pub maximized: bool,
+    /// Set once the sidebar has been dragged to a fixed width
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sidebar_width: Option<i32>,
+    /// Executable name of the selected profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected_profile: Option<String>,
+    /// `SortMode::key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_mode: Option<String>,
+    /// `FilterChip::key` of each active chip
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filter_chips: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filter_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collapsed_groups: Vec<String>,
+}
+
+impl UiState {
+    pub fn sort_mode(&self) -> Option<SortMode> {
+        self.sort_mode.as_deref().and_then(SortMode::from_key)
+    }
+
+    pub fn filter_chips(&self) -> Vec<FilterChip> {
+        self.filter_chips.iter().filter_map(|key| FilterChip::from_key(key)).collect()
+    }
+}
+
+// --- UI State File Handling Functions ---
+
+pub fn get_ui_state_path() -> Result<PathBuf, io::Error> {
+    let state_dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Could not find state directory"))?
+        .join("lsfg-vk-ui");
+    fs::create_dir_all(&state_dir)?;
+    Ok(state_dir.join("ui-state.toml"))
+}
+
+pub fn load_ui_state() -> Result<UiState, io::Error> {
+    let path = get_ui_state_path()?;
+    if !path.exists() {
+        return Ok(UiState::default());
+    }
+    let contents = fs::read_to_string(&path)?;
+    toml::from_str(&contents).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("Failed to parse UI state: {}", e))
+    })
+}
+
+pub fn save_ui_state(state: &UiState) -> Result<(), io::Error> {
+    let path = get_ui_state_path()?;
+    let toml_string = toml::to_string_pretty(state)
+        .map_err(|e| io::Error::other(format!("Failed to serialize UI state: {}", e)))?;
+    fs::write(&path, toml_string)?;
+    Ok(())
+}
@@ -1,5 +1,6 @@
-use std::process::Command;
-use std::io::{BufReader, BufRead};
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
 
 pub fn round_to_2_decimals(value: f32) -> f32 {
     // Use string formatting to get exactly 2 decimal places and then parse back
@@ -7,54 +8,65 @@ pub fn round_to_2_decimals(value: f32) -> f32 {
     format!("{:.2}", value).parse().unwrap_or(value)
 }
 
-/// Executes a bash command to find running processes that use Vulkan
-/// and are owned by the current user.
-/// Returns a vector of process descriptions with PID and name.
-pub fn get_vulkan_processes() -> Vec<String> {
-    let mut processes = Vec::new();
-    let command_str = r#"
-        for pid in /proc/[0-9]*; do
-            owner=$(stat -c %U "$pid" 2>/dev/null)
-            if [[ "$owner" == "$USER" ]]; then
-                if grep -qi 'vulkan' "$pid/maps" 2>/dev/null; then
-                    procname=$(cat "$pid/comm" 2>/dev/null)
-                    if [[ -n "$procname" ]]; then
-                        printf "PID %s: %s\n" "$(basename "$pid")" "$procname"
-                    fi
-                fi
-            fi
-        done
-    "#;
+/// Seconds since the Unix epoch, or 0 if the clock is before it
+pub fn unix_time_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
 
-    // Execute the bash command
-    let output = Command::new("bash")
-        .arg("-c")
-        .arg(command_str)
-        .output();
+/// A process of the current user that has Vulkan loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct VulkanProcess {
+    pub pid: u32,
+    /// Full executable name; `comm` is cut to 15 characters, so it comes from
+    /// argv[0] in `cmdline`, or from the `exe` link when that is empty
+    pub name: String,
+}
 
-    match output {
-        Ok(output) => {
-            if output.status.success() {
-                // Read stdout line by line
-                let reader = BufReader::new(output.stdout.as_slice());
-                for line in reader.lines() {
-                    if let Ok(proc_info) = line {
-                        let trimmed_info = proc_info.trim().to_string();
-                        if !trimmed_info.is_empty() {
-                            processes.push(trimmed_info);
-                        }
-                    }
-                }
-            } else {
-                // Print stderr if the command failed
-                eprintln!("Command failed with error: {}", String::from_utf8_lossy(&output.stderr));
-            }
-        },
-        Err(e) => {
-            // Print error if the command could not be executed
-            eprintln!("Failed to execute command: {}", e);
-        }
+impl VulkanProcess {
+    /// How the process picker lists it, e.g. "PID 1234: game.exe"
+    pub fn describe(&self) -> String {
+        format!("PID {}: {}", self.pid, self.name)
     }
+}
+
+/// Finds the current user's running processes that use Vulkan, ordered by PID
+pub fn get_vulkan_processes() -> Vec<VulkanProcess> {
+    let Ok(uid) = fs::metadata("/proc/self").map(|metadata| metadata.uid()) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir("/proc") else {
+        eprintln!("Failed to read /proc");
+        return Vec::new();
+    };
+    let mut processes: Vec<VulkanProcess> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Some((entry.file_name().to_str()?.parse::<u32>().ok()?, entry)))
+        .filter(|(_, entry)| entry.metadata().is_ok_and(|metadata| metadata.uid() == uid))
+        .filter(|(_, entry)| {
+            fs::read_to_string(entry.path().join("maps"))
+                .is_ok_and(|maps| maps.to_lowercase().contains("vulkan"))
+        })
+        .filter_map(|(pid, entry)| Some(VulkanProcess { pid, name: process_name(&entry.path())? }))
+        .collect();
+    processes.sort_by_key(|process| process.pid);
     processes
 }
 
+fn process_name(proc_dir: &Path) -> Option<String> {
+    let from_cmdline = fs::read(proc_dir.join("cmdline")).ok().and_then(|cmdline| {
+        let argv0 = cmdline.split(|byte| *byte == 0).next()?;
+        executable_basename(&String::from_utf8_lossy(argv0))
+    });
+    from_cmdline.or_else(|| {
+        let exe = fs::read_link(proc_dir.join("exe")).ok()?;
+        executable_basename(&exe.to_string_lossy())
+    })
+}
+
+// Wine games report Windows paths in argv[0], so both separators are split on
+fn executable_basename(path: &str) -> Option<String> {
+    let name = path.rsplit(['/', '\\']).next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
@@ -0,0 +1,287 @@
+//! Minimal reader/writer for Valve's text KeyValues (VDF) format
+//! Written output follows Steam's own layout (tab indentation, braces on their
+//! own lines, two tabs between key and value) so untouched files round-trip unchanged.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VdfValue {
+    String(String),
+    Object(Vec<VdfNode>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VdfNode {
+    pub key: String,
+    pub value: VdfValue,
+    /// Platform conditional such as `[$WIN32]`, kept so it is written back
+    pub condition: Option<String>,
+}
+
+impl VdfNode {
+    /// Child nodes of an object; empty for string values
+    pub fn children(&self) -> &[VdfNode] {
+        match &self.value {
+            VdfValue::Object(children) => children,
+            VdfValue::String(_) => &[],
+        }
+    }
+
+    /// Finds a direct child by key. Steam is inconsistent about key case ("apps" vs "Apps").
+    pub fn child(&self, key: &str) -> Option<&VdfNode> {
+        self.children().iter().find(|node| node.key.eq_ignore_ascii_case(key))
+    }
+
+    /// Finds or creates a child object; a string value in the way is replaced by an object.
+    /// Like `set_string` it only edits children without a conditional.
+    pub fn child_object_mut(&mut self, key: &str) -> &mut VdfNode {
+        if !matches!(self.value, VdfValue::Object(_)) {
+            self.value = VdfValue::Object(Vec::new());
+        }
+        let VdfValue::Object(children) = &mut self.value else {
+            unreachable!("value was just made an object");
+        };
+        let position = match children.iter().position(|node| node.is_unconditional(key)) {
+            Some(position) => position,
+            None => {
+                children.push(VdfNode { key: key.to_string(), value: VdfValue::Object(Vec::new()), condition: None });
+                children.len() - 1
+            }
+        };
+        let node = &mut children[position];
+        if !matches!(node.value, VdfValue::Object(_)) {
+            node.value = VdfValue::Object(Vec::new());
+        }
+        node
+    }
+
+    /// Sets (or adds) a string child; children behind a conditional are left alone
+    pub fn set_string(&mut self, key: &str, value: &str) {
+        if !matches!(self.value, VdfValue::Object(_)) {
+            self.value = VdfValue::Object(Vec::new());
+        }
+        let VdfValue::Object(children) = &mut self.value else {
+            unreachable!("value was just made an object");
+        };
+        match children.iter_mut().find(|node| node.is_unconditional(key)) {
+            Some(node) => node.value = VdfValue::String(value.to_string()),
+            None => children.push(VdfNode { key: key.to_string(), value: VdfValue::String(value.to_string()), condition: None }),
+        }
+    }
+
+    fn is_unconditional(&self, key: &str) -> bool {
+        self.condition.is_none() && self.key.eq_ignore_ascii_case(key)
+    }
+
+    pub fn string(&self) -> Option<&str> {
+        match &self.value {
+            VdfValue::String(value) => Some(value),
+            VdfValue::Object(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Text(String),
+    /// `[$WIN32]` and the like, including the brackets
+    Condition(String),
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '/' if chars.peek() == Some(&'/') => {
+                // Line comment
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '[' => {
+                let mut text = c.to_string();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(other) => text.push(other),
+                        None => return Err("Unterminated conditional".to_string()),
+                    }
+                }
+                text.push(']');
+                tokens.push(Token::Condition(text));
+            }
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(other) => text.push(other),
+                            None => return Err("Unterminated escape sequence".to_string()),
+                        },
+                        Some(other) => text.push(other),
+                        None => return Err("Unterminated quoted string".to_string()),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            _ => {
+                // Unquoted token
+                let mut text = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == '{' || next == '}' || next == '"' {
+                        break;
+                    }
+                    text.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Text(text));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses a VDF document into its top-level nodes
+pub fn parse_vdf(input: &str) -> Result<Vec<VdfNode>, String> {
+    let tokens = tokenize(input)?;
+    let mut position = 0;
+    let nodes = parse_nodes(&tokens, &mut position)?;
+    if position != tokens.len() {
+        return Err("Unexpected closing brace".to_string());
+    }
+    Ok(nodes)
+}
+
+fn parse_nodes(tokens: &[Token], position: &mut usize) -> Result<Vec<VdfNode>, String> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.get(*position) {
+        let key = match token {
+            Token::Close => break,
+            Token::Open => return Err("Expected a key but found '{'".to_string()),
+            Token::Condition(condition) => return Err(format!("Expected a key but found '{}'", condition)),
+            Token::Text(key) => key.clone(),
+        };
+        *position += 1;
+        // Objects carry their conditional after the key, strings after the value
+        let mut condition = take_condition(tokens, position);
+
+        let value = match tokens.get(*position) {
+            Some(Token::Text(value)) => {
+                *position += 1;
+                condition = condition.or_else(|| take_condition(tokens, position));
+                VdfValue::String(value.clone())
+            }
+            Some(Token::Open) => {
+                *position += 1;
+                let children = parse_nodes(tokens, position)?;
+                if tokens.get(*position) != Some(&Token::Close) {
+                    return Err(format!("Missing closing brace for '{}'", key));
+                }
+                *position += 1;
+                VdfValue::Object(children)
+            }
+            _ => return Err(format!("Missing value for '{}'", key)),
+        };
+        nodes.push(VdfNode { key, value, condition });
+    }
+    Ok(nodes)
+}
+
+fn take_condition(tokens: &[Token], position: &mut usize) -> Option<String> {
+    match tokens.get(*position) {
+        Some(Token::Condition(condition)) => {
+            *position += 1;
+            Some(condition.clone())
+        }
+        _ => None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+/// Serialises nodes in Steam's layout
+pub fn write_vdf(nodes: &[VdfNode]) -> String {
+    let mut output = String::new();
+    write_nodes(nodes, 0, &mut output);
+    output
+}
+
+fn write_nodes(nodes: &[VdfNode], depth: usize, output: &mut String) {
+    let indent = "\t".repeat(depth);
+    for node in nodes {
+        let condition = node.condition.as_ref().map_or(String::new(), |condition| format!("\t\t{}", condition));
+        match &node.value {
+            VdfValue::String(value) => {
+                output.push_str(&format!("{}\"{}\"\t\t\"{}\"{}\n", indent, escape(&node.key), escape(value), condition));
+            }
+            VdfValue::Object(children) => {
+                output.push_str(&format!("{}\"{}\"{}\n{}{{\n", indent, escape(&node.key), condition, indent));
+                write_nodes(children, depth + 1, output);
+                output.push_str(&format!("{}}}\n", indent));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALCONFIG: &str = include_str!("../tests/fixtures/localconfig.vdf");
+    const LOCALCONFIG_CONDITIONALS: &str = include_str!("../tests/fixtures/localconfig_conditionals.vdf");
+
+    #[test]
+    fn round_trips_localconfig() {
+        let nodes = parse_vdf(LOCALCONFIG).unwrap();
+        assert_eq!(write_vdf(&nodes), LOCALCONFIG);
+    }
+
+    #[test]
+    fn round_trips_conditionals() {
+        let nodes = parse_vdf(LOCALCONFIG_CONDITIONALS).unwrap();
+        assert_eq!(write_vdf(&nodes), LOCALCONFIG_CONDITIONALS);
+
+        let console = nodes[0].child("Console").unwrap();
+        assert_eq!(console.condition.as_deref(), Some("[$X360]"));
+    }
+
+    #[test]
+    fn keeps_escapes() {
+        let nodes = parse_vdf(LOCALCONFIG).unwrap();
+        let name = nodes[0].child("friends").and_then(|node| node.child("PersonaName"));
+        assert_eq!(name.and_then(VdfNode::string), Some("Player \"One\""));
+    }
+
+    #[test]
+    fn set_string_skips_conditional_children() {
+        let mut nodes = parse_vdf("\"app\"\n{\n\t\"LaunchOptions\"\t\t\"-dx11\"\t\t[$WIN32]\n}\n").unwrap();
+        nodes[0].set_string("LaunchOptions", "%command%");
+        assert_eq!(
+            write_vdf(&nodes),
+            "\"app\"\n{\n\t\"LaunchOptions\"\t\t\"-dx11\"\t\t[$WIN32]\n\t\"LaunchOptions\"\t\t\"%command%\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn rejects_broken_input() {
+        assert!(parse_vdf("\"key\"\n{\n").is_err());
+        assert!(parse_vdf("}").is_err());
+        assert!(parse_vdf("\"key\"\t\t\"value").is_err());
+        assert!(parse_vdf("\"key\"\t\t\"value\"\t\t[$WIN32").is_err());
+    }
+}